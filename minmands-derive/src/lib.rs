@@ -0,0 +1,324 @@
+//! `#[derive(Command)]` for `minmands`. See `minmands::Command` for an example.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Expr, Fields,
+    LitStr, Token, Type,
+};
+
+/// Derives `minmands::Command` for an enum.
+///
+/// Variants annotated with `#[command("!literal", "!alias", ...)]` get a command tree
+/// that starts with one of the literals and then parses every field as an argument,
+/// named after the field (or its index for tuple variants).
+///
+/// The argument type is inferred from the field type (`String` is a word, `i64` an integer,
+/// `f64` a float, `Duration` a duration, `Option<_>` an optional argument),
+/// or can be set explicitly with `#[argument(...)]`:
+/// - `word`, `line`, `tail`, `quoted`, `int`, `int(min, max)`, `float`, `duration`, `user`
+/// - `regex("pattern")`
+/// - `choice("a", "b", ...)`
+/// - `optional` to allow omitting the argument (the field has to be an `Option`)
+/// - `default = <value>` to allow omitting the argument and use the value instead
+///
+/// Choices cannot be optional.
+///
+/// Field types must implement `Default`, which is used for placeholder values.
+#[proc_macro_derive(Command, attributes(command, argument))]
+pub fn derive_command(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Command can only be derived for enums",
+        ));
+    };
+
+    let mut nodes = Vec::new();
+    let mut fillers = Vec::new();
+    for variant in &data.variants {
+        let Some(attr) = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("command"))
+        else {
+            continue;
+        };
+        let literals = attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+        if literals.is_empty() {
+            return Err(syn::Error::new(
+                attr.span(),
+                "Expected at least one literal, e.g. #[command(\"!hello\")]",
+            ));
+        }
+
+        let ident = &variant.ident;
+        let mut arguments = Vec::new();
+        let mut names = Vec::new();
+        for (index, field) in variant.fields.iter().enumerate() {
+            let name = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => index.to_string(),
+            };
+            let argument = Argument::from_field(field)?;
+            arguments.push(argument.builder_call(&name));
+            names.push(name);
+        }
+
+        let (placeholder, filled) = match &variant.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                (
+                    quote! { Self::#ident { #(#idents: ::core::default::Default::default()),* } },
+                    quote! { Self::#ident { #(#idents: arguments.get(#names)?),* } },
+                )
+            }
+            Fields::Unnamed(fields) => {
+                let defaults = fields
+                    .unnamed
+                    .iter()
+                    .map(|_| quote! { ::core::default::Default::default() });
+                (
+                    quote! { Self::#ident(#(#defaults),*) },
+                    quote! { Self::#ident(#(arguments.get(#names)?),*) },
+                )
+            }
+            Fields::Unit => (quote! { Self::#ident }, quote! { Self::#ident }),
+        };
+
+        let literals = literals.iter();
+        nodes.push(quote! {
+            ::minmands::CommandBuilder::new()
+                .literal([#(#literals),*])
+                #(#arguments)*
+                .finalize(true, #placeholder)
+        });
+        fillers.push(quote! {
+            Self::#ident { .. } => #filled,
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::minmands::Command for #name #type_generics #where_clause {
+            fn command_nodes() -> ::std::vec::Vec<::minmands::CommandNode<Self>> {
+                ::std::vec![#(#nodes),*]
+            }
+
+            #[allow(unused_variables)]
+            fn with_arguments(self, arguments: &::minmands::Arguments) -> ::core::option::Option<Self> {
+                ::core::option::Option::Some(match self {
+                    #(#fillers)*
+                    #[allow(unreachable_patterns)]
+                    other => other,
+                })
+            }
+        }
+    })
+}
+
+/// How a field is parsed.
+struct Argument {
+    kind: ArgumentKind,
+    optional: bool,
+    default: Option<Expr>,
+}
+
+enum ArgumentKind {
+    /// An expression of type `minmands::ArgumentType`.
+    Type(TokenStream),
+    Choice(Vec<LitStr>),
+}
+
+impl Argument {
+    fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut argument = match infer_argument(&field.ty) {
+            Some(argument) => argument,
+            None => Argument {
+                kind: ArgumentKind::Type(TokenStream::new()),
+                optional: false,
+                default: None,
+            },
+        };
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("argument") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                let path = &meta.path;
+                let ident = path
+                    .get_ident()
+                    .ok_or_else(|| meta.error("Expected an argument type"))?
+                    .to_string();
+                match ident.as_str() {
+                    "optional" => argument.optional = true,
+                    "default" => argument.default = Some(meta.value()?.parse()?),
+                    "choice" => {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        let choices = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                        argument.kind = ArgumentKind::Choice(choices.into_iter().collect());
+                    }
+                    "int" if meta.input.peek(syn::token::Paren) => {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        let range = Punctuated::<Expr, Token![,]>::parse_terminated(&content)?;
+                        let [min, max]: [&Expr; 2] = range
+                            .iter()
+                            .collect::<Vec<_>>()
+                            .try_into()
+                            .map_err(|_| meta.error("Expected int(min, max)"))?;
+                        argument.kind = ArgumentKind::Type(quote! {
+                            ::minmands::ArgumentType::Integer {
+                                min: ::core::option::Option::Some(#min),
+                                max: ::core::option::Option::Some(#max),
+                            }
+                        });
+                    }
+                    "regex" => {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        let pattern: LitStr = content.parse()?;
+                        if let Err(err) = regex_syntax::parse(&pattern.value()) {
+                            return Err(syn::Error::new(pattern.span(), err));
+                        }
+                        argument.kind = ArgumentKind::Type(quote! {
+                            ::minmands::ArgumentType::Regex(
+                                ::minmands::Pattern::new(#pattern).unwrap()
+                            )
+                        });
+                    }
+                    _ => {
+                        let argument_type = simple_argument_type(&ident)
+                            .ok_or_else(|| meta.error("Unknown argument type"))?;
+                        argument.kind = ArgumentKind::Type(argument_type);
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        if let ArgumentKind::Type(tokens) = &argument.kind {
+            if tokens.is_empty() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "Cannot infer the argument type, specify it with #[argument(...)]",
+                ));
+            }
+        }
+        if let ArgumentKind::Choice(_) = &argument.kind {
+            if argument.optional || argument.default.is_some() {
+                return Err(syn::Error::new(field.span(), "Choices cannot be optional"));
+            }
+        }
+        if argument.optional && !is_option(&field.ty) {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "An `optional` argument has to be an `Option`",
+            ));
+        }
+        if argument.optional && argument.default.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                "An argument cannot be both `optional` and have a `default`",
+            ));
+        }
+        Ok(argument)
+    }
+
+    /// Call to `minmands::CommandBuilder` that adds the argument.
+    fn builder_call(&self, name: &str) -> TokenStream {
+        let node = match (&self.kind, self.optional, &self.default) {
+            (ArgumentKind::Choice(choices), _, _) => {
+                quote! { .choice([#(#choices),*]) }
+            }
+            (ArgumentKind::Type(argument_type), false, None) => {
+                quote! { .argument(#argument_type) }
+            }
+            (ArgumentKind::Type(argument_type), true, _) => {
+                quote! { .optional(#argument_type) }
+            }
+            (ArgumentKind::Type(argument_type), false, Some(default)) => {
+                quote! { .optional_or(#argument_type, #default) }
+            }
+        };
+        quote! { #node.named(#name) }
+    }
+}
+
+/// Infers the argument from the field type.
+fn infer_argument(ty: &Type) -> Option<Argument> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let ident = segment.ident.to_string();
+    if ident == "Option" {
+        let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+            return None;
+        };
+        let Some(syn::GenericArgument::Type(inner)) = args.args.first() else {
+            return None;
+        };
+        let mut argument = infer_argument(inner)?;
+        argument.optional = true;
+        return Some(argument);
+    }
+
+    let argument_type = match ident.as_str() {
+        "String" => simple_argument_type("word"),
+        "i64" => simple_argument_type("int"),
+        "f64" => simple_argument_type("float"),
+        "Duration" => simple_argument_type("duration"),
+        _ => None,
+    }?;
+    Some(Argument {
+        kind: ArgumentKind::Type(argument_type),
+        optional: false,
+        default: None,
+    })
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Option")
+}
+
+/// Argument types that have no parameters.
+fn simple_argument_type(ident: &str) -> Option<TokenStream> {
+    let variant = match ident {
+        "word" => "Word",
+        "line" => "Line",
+        "tail" => "Tail",
+        "quoted" => "Quoted",
+        "float" => "Float",
+        "duration" => "Duration",
+        "user" => "User",
+        "int" => {
+            return Some(quote! {
+                ::minmands::ArgumentType::Integer {
+                    min: ::core::option::Option::None,
+                    max: ::core::option::Option::None,
+                }
+            })
+        }
+        _ => return None,
+    };
+    let variant = format_ident!("{}", variant);
+    Some(quote! { ::minmands::ArgumentType::#variant })
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use minmands::{Command, CommandNode};
+
+#[derive(Debug, Clone, PartialEq, Command)]
+enum Action {
+    #[command("!reload")]
+    Reload,
+    #[command("!hello", "!hi")]
+    Hello {
+        #[argument(word, default = "everyone")]
+        name: String,
+    },
+    #[command("!roll")]
+    Roll {
+        #[argument(int(1, 100))]
+        sides: i64,
+        times: Option<i64>,
+    },
+    #[command("!backup")]
+    Backup(#[argument(choice("create", "load"))] String),
+    #[command("!timeout")]
+    Timeout { user: String, time: Duration },
+    #[command("!quote")]
+    Quote {
+        #[argument(quoted)]
+        text: String,
+    },
+    /// Not a command
+    Say(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Command)]
+enum Moderation {
+    #[command("!ban")]
+    Ban {
+        #[argument(user)]
+        user: String,
+    },
+    #[command("!color")]
+    Color(#[argument(regex("#[0-9a-fA-F]{6}"))] String),
+}
+
+fn parse<T: Command>(nodes: &[CommandNode<T>], message: &str) -> Option<T> {
+    nodes.iter().find_map(|node| {
+        let parsed = node.parse(message).ok()?;
+        parsed.value.with_arguments(&parsed.arguments)
+    })
+}
+
+#[test]
+fn test_derive() {
+    let nodes = Action::command_nodes();
+    assert_eq!(nodes.len(), 6);
+
+    assert_eq!(parse(&nodes, "!reload"), Some(Action::Reload));
+    assert_eq!(
+        parse(&nodes, "!hi"),
+        Some(Action::Hello {
+            name: "everyone".to_string()
+        })
+    );
+    assert_eq!(
+        parse(&nodes, "!hello bob"),
+        Some(Action::Hello {
+            name: "bob".to_string()
+        })
+    );
+    assert_eq!(
+        parse(&nodes, "!roll 20"),
+        Some(Action::Roll {
+            sides: 20,
+            times: None
+        })
+    );
+    assert_eq!(
+        parse(&nodes, "!roll 20 3"),
+        Some(Action::Roll {
+            sides: 20,
+            times: Some(3)
+        })
+    );
+    assert_eq!(parse(&nodes, "!roll 200"), None);
+    assert_eq!(
+        parse(&nodes, "!backup load"),
+        Some(Action::Backup("load".to_string()))
+    );
+    assert_eq!(
+        parse(&nodes, "!timeout bob 10m"),
+        Some(Action::Timeout {
+            user: "bob".to_string(),
+            time: Duration::from_secs(600)
+        })
+    );
+    assert_eq!(
+        parse(&nodes, r#"!quote "a b c""#),
+        Some(Action::Quote {
+            text: "a b c".to_string()
+        })
+    );
+    assert_eq!(parse(&nodes, "say"), None);
+    assert_eq!(
+        Action::Say("hi".to_string()).with_arguments(&Default::default()),
+        Some(Action::Say("hi".to_string()))
+    );
+
+    assert_eq!(nodes[2].usage(), vec!["!roll <sides> [times]"]);
+}
+
+#[test]
+fn test_derive_validated() {
+    let nodes = Moderation::command_nodes();
+    assert_eq!(
+        parse(&nodes, "!ban @Nertsal"),
+        Some(Moderation::Ban {
+            user: "nertsal".to_string()
+        })
+    );
+    assert_eq!(parse(&nodes, "!ban no!"), None);
+    assert_eq!(
+        parse(&nodes, "!color #00ff00"),
+        Some(Moderation::Color("#00ff00".to_string()))
+    );
+    assert_eq!(parse(&nodes, "!color green"), None);
+}
@@ -0,0 +1,343 @@
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::ArgumentValue;
+
+/// Describes how an argument is parsed from the message.
+///
+/// Serialized as a lowercase name, e.g. `"word"`,
+/// or as a table for types with parameters, e.g. `{ integer = { min = 1 } }`
+/// or `{ regex = "#[0-9a-fA-F]{6}" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgumentType {
+    /// Parse a single word.
+    Word,
+    /// Parse till the end of line.
+    Line,
+    /// Parse till the end of input.
+    Tail,
+    /// Parse a single word as a whole number.
+    /// Optionally, the number can be limited to an inclusive range.
+    Integer {
+        #[serde(default)]
+        min: Option<i64>,
+        #[serde(default)]
+        max: Option<i64>,
+    },
+    /// Parse a single word as a floating point number.
+    Float,
+    /// Parse a single word as a duration, e.g. `90s`, `10m` or `1h30m`.
+    /// A plain number is interpreted as seconds.
+    Duration,
+    /// Parse a string enclosed in `"` or `'` quotes, or a single word if there are no quotes.
+    /// Inside quotes, a backslash escapes the next character, e.g. `\"` or `\\`.
+    Quoted,
+    /// Parse the text matching the pattern at the start of the message.
+    /// The match may span several words, but has to end at a word boundary.
+    Regex(Pattern),
+    /// Parse a single word as a Twitch user, e.g. `@name` or `name`.
+    /// The leading `@` is stripped and the login is lowercased.
+    /// A login is 4 to 25 letters, digits or underscores, and cannot start with an underscore.
+    User,
+}
+
+/// A regular expression for [ArgumentType::Regex].
+/// Compared and serialized as the source pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern {
+    source: String,
+    /// The source pattern, anchored at the start.
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(source: impl Into<String>) -> Result<Self, regex::Error> {
+        let source = source.into();
+        let regex = Regex::new(&format!("^(?:{source})"))?;
+        Ok(Self { source, regex })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = regex::Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::new(source)
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.source
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}/", self.source)
+    }
+}
+
+impl std::fmt::Display for ArgumentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgumentType::Word => write!(f, "Word"),
+            ArgumentType::Line => write!(f, "Line"),
+            ArgumentType::Tail => write!(f, "Tail"),
+            ArgumentType::Integer { min, max } => {
+                write!(f, "Integer")?;
+                match (min, max) {
+                    (None, None) => Ok(()),
+                    (Some(min), None) => write!(f, " (at least {min})"),
+                    (None, Some(max)) => write!(f, " (at most {max})"),
+                    (Some(min), Some(max)) => write!(f, " (from {min} to {max})"),
+                }
+            }
+            ArgumentType::Float => write!(f, "Float"),
+            ArgumentType::Duration => write!(f, "Duration"),
+            ArgumentType::Quoted => write!(f, "Quoted"),
+            ArgumentType::Regex(pattern) => write!(f, "Text matching {pattern}"),
+            ArgumentType::User => write!(f, "User"),
+        }
+    }
+}
+
+impl ArgumentType {
+    /// Attempts to parse the argument from the start of the message.
+    /// On success, returns the parsed value and the slice of the message that was consumed.
+    pub(crate) fn parse<'a>(&self, message: &'a str) -> Result<(ArgumentValue, &'a str), String> {
+        if let ArgumentType::Quoted = self {
+            if let Some(result) = parse_quoted(message) {
+                return result.map(|(value, argument)| (ArgumentValue::String(value), argument));
+            }
+        }
+
+        let argument = match self {
+            ArgumentType::Word
+            | ArgumentType::Quoted
+            | ArgumentType::Integer { .. }
+            | ArgumentType::Float
+            | ArgumentType::Duration
+            | ArgumentType::User => message.split_whitespace().next(),
+            ArgumentType::Line => message.lines().next(),
+            ArgumentType::Regex(pattern) => pattern
+                .regex
+                .find(message)
+                .map(|found| found.as_str())
+                .filter(|argument| {
+                    let rest = &message[argument.len()..];
+                    !argument.is_empty()
+                        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+                }),
+            ArgumentType::Tail => {
+                if message.trim().is_empty() {
+                    None
+                } else {
+                    Some(message)
+                }
+            }
+        }
+        .ok_or_else(|| match self {
+            ArgumentType::Regex(pattern) => format!("expected text matching {pattern}"),
+            _ => format!("expected a {self} argument"),
+        })?;
+
+        let value = match self {
+            ArgumentType::Word
+            | ArgumentType::Line
+            | ArgumentType::Tail
+            | ArgumentType::Quoted
+            | ArgumentType::Regex(_) => ArgumentValue::String(argument.to_owned()),
+            ArgumentType::User => {
+                let login = argument.strip_prefix('@').unwrap_or(argument);
+                if !is_twitch_login(login) {
+                    return Err(format!("expected a Twitch user, found {argument:?}"));
+                }
+                ArgumentValue::String(login.to_lowercase())
+            }
+            ArgumentType::Integer { min, max } => {
+                let value: i64 = argument
+                    .parse()
+                    .map_err(|_| format!("expected an integer, found {argument:?}"))?;
+                if let Some(min) = min {
+                    if value < *min {
+                        return Err(format!("expected at least {min}, found {value}"));
+                    }
+                }
+                if let Some(max) = max {
+                    if value > *max {
+                        return Err(format!("expected at most {max}, found {value}"));
+                    }
+                }
+                ArgumentValue::Integer(value)
+            }
+            ArgumentType::Float => argument
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .map(ArgumentValue::Float)
+                .ok_or_else(|| format!("expected a number, found {argument:?}"))?,
+            ArgumentType::Duration => parse_duration(argument)
+                .map(ArgumentValue::Duration)
+                .ok_or_else(|| format!("expected a duration, found {argument:?}"))?,
+        };
+
+        Ok((value, argument))
+    }
+}
+
+/// Checks the Twitch login rules: 4 to 25 ASCII letters, digits or underscores,
+/// not starting with an underscore.
+pub fn is_twitch_login(login: &str) -> bool {
+    (4..=25).contains(&login.len())
+        && !login.starts_with('_')
+        && login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parses a quoted string from the start of the message, unescaping the contents.
+/// Returns `None` if the message does not start with a quote.
+/// On success, returns the unescaped string and the consumed slice, including the quotes.
+fn parse_quoted(message: &str) -> Option<Result<(String, &str), String>> {
+    let mut chars = message.char_indices();
+    let quote = match chars.next() {
+        Some((_, quote @ ('"' | '\''))) => quote,
+        _ => return None,
+    };
+
+    let mut value = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped)) => value.push(escaped),
+                None => break,
+            },
+            c if c == quote => {
+                let end = i + c.len_utf8();
+                return Some(Ok((value, &message[..end])));
+            }
+            c => value.push(c),
+        }
+    }
+
+    Some(Err(format!("missing a closing {quote} quote")))
+}
+
+/// Parses a human readable duration, like `10m`, `1h30m` or `1.5h`.
+/// Supported units are `d`, `h`, `m`, `s` and `ms`.
+/// A plain number is interpreted as seconds.
+pub fn parse_duration(source: &str) -> Option<Duration> {
+    if source.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = source.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+
+    let mut total = 0.0;
+    let mut rest = source;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let unit_len = rest[number_len..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len() - number_len);
+        let number: f64 = rest[..number_len].parse().ok()?;
+        let unit = match &rest[number_len..number_len + unit_len] {
+            "d" => 24.0 * 60.0 * 60.0,
+            "h" => 60.0 * 60.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += number * unit;
+        rest = &rest[number_len + unit_len..];
+    }
+
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Some(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("10x"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("-5"), None);
+    }
+
+    #[test]
+    fn test_parse_quoted() {
+        let ty = ArgumentType::Quoted;
+        let value = |s: &str| ArgumentValue::String(s.to_owned());
+        assert_eq!(
+            ty.parse(r#""some long quote" @author"#),
+            Ok((value("some long quote"), r#""some long quote""#))
+        );
+        assert_eq!(
+            ty.parse(r#"'it\'s \\ "fine"' rest"#),
+            Ok((value(r#"it's \ "fine""#), r#"'it\'s \\ "fine"'"#))
+        );
+        assert_eq!(ty.parse("bare word"), Ok((value("bare"), "bare")));
+        assert!(ty.parse(r#""unterminated"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        let ty = ArgumentType::Integer {
+            min: Some(1),
+            max: Some(20),
+        };
+        assert_eq!(ty.parse("20 rest"), Ok((ArgumentValue::Integer(20), "20")));
+        assert!(ty.parse("0").is_err());
+        assert!(ty.parse("21").is_err());
+        assert!(ty.parse("twenty").is_err());
+    }
+
+    #[test]
+    fn test_parse_regex() {
+        let ty = ArgumentType::Regex(Pattern::new("#[0-9a-fA-F]{6}").unwrap());
+        let value = ArgumentValue::String("#ff00AA".to_owned());
+        assert_eq!(ty.parse("#ff00AA rest"), Ok((value, "#ff00AA")));
+        assert!(ty.parse("#ff00AAB").is_err());
+        assert!(ty.parse("ff00AA").is_err());
+
+        let ty = ArgumentType::Regex(Pattern::new(r"\w+ \w+").unwrap());
+        assert_eq!(ty.parse("two words rest").unwrap().1, "two words");
+    }
+
+    #[test]
+    fn test_parse_user() {
+        let ty = ArgumentType::User;
+        let value = ArgumentValue::String("nertsal".to_owned());
+        assert_eq!(ty.parse("@Nertsal hi"), Ok((value.clone(), "@Nertsal")));
+        assert_eq!(ty.parse("nertsal"), Ok((value, "nertsal")));
+        assert!(ty.parse("@abc").is_err());
+        assert!(ty.parse("_nertsal").is_err());
+        assert!(ty.parse("nert-sal").is_err());
+        assert!(ty.parse("@").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ArgumentType, CommandNode};
+use crate::{ArgumentType, ArgumentValue, CommandNode, Pattern};
 
 /// Helps to easier create trees of [CommandNode]. Makes construction of
 /// deep command trees more concise. Conversely, constructing
@@ -29,6 +29,13 @@ impl<T: Clone> CommandBuilder<T> {
         self
     }
 
+    /// Adds a node that expects an argument of the given type
+    pub fn argument(mut self, argument_type: ArgumentType) -> Self {
+        self.nodes
+            .push(CommandNode::argument(argument_type, vec![]));
+        self
+    }
+
     /// Adds a literal node that takes a line as an argument
     pub fn line(mut self) -> Self {
         self.nodes
@@ -36,6 +43,53 @@ impl<T: Clone> CommandBuilder<T> {
         self
     }
 
+    /// Adds a node that expects a quoted string argument, or a word if there are no quotes
+    pub fn quoted(mut self) -> Self {
+        self.nodes
+            .push(CommandNode::argument(ArgumentType::Quoted, vec![]));
+        self
+    }
+
+    /// Adds a node that expects a whole number argument,
+    /// optionally limited to an inclusive range
+    pub fn integer(mut self, min: Option<i64>, max: Option<i64>) -> Self {
+        self.nodes.push(CommandNode::argument(
+            ArgumentType::Integer { min, max },
+            vec![],
+        ));
+        self
+    }
+
+    /// Adds a node that expects a floating point number argument
+    pub fn float(mut self) -> Self {
+        self.nodes
+            .push(CommandNode::argument(ArgumentType::Float, vec![]));
+        self
+    }
+
+    /// Adds a node that expects a duration argument, like `10m` or `1h30m`
+    pub fn duration(mut self) -> Self {
+        self.nodes
+            .push(CommandNode::argument(ArgumentType::Duration, vec![]));
+        self
+    }
+
+    /// Adds a node that expects a Twitch user, like `@name` or `name`
+    pub fn user(mut self) -> Self {
+        self.nodes
+            .push(CommandNode::argument(ArgumentType::User, vec![]));
+        self
+    }
+
+    /// Adds a node that expects text matching the regex pattern.
+    /// Panics if the pattern is invalid
+    pub fn regex(mut self, pattern: &str) -> Self {
+        let pattern = Pattern::new(pattern).expect("Invalid regex pattern");
+        self.nodes
+            .push(CommandNode::argument(ArgumentType::Regex(pattern), vec![]));
+        self
+    }
+
     /// Adds a literal node that accepts only certain literals
     /// and forwards the chosen one as an argument
     pub fn choice(mut self, choices: impl IntoIterator<Item = impl Into<String>>) -> Self {
@@ -44,12 +98,58 @@ impl<T: Clone> CommandBuilder<T> {
         self
     }
 
+    /// Adds a node that expects an argument that may be omitted.
+    /// If omitted, the argument's value is [ArgumentValue::Empty]
+    pub fn optional(mut self, argument_type: ArgumentType) -> Self {
+        self.nodes
+            .push(CommandNode::optional(argument_type, None, vec![]));
+        self
+    }
+
+    /// Adds a node that expects an argument that may be omitted,
+    /// in which case the default value is used
+    pub fn optional_or(
+        mut self,
+        argument_type: ArgumentType,
+        default: impl Into<ArgumentValue>,
+    ) -> Self {
+        self.nodes.push(CommandNode::optional(
+            argument_type,
+            Some(default.into()),
+            vec![],
+        ));
+        self
+    }
+
+    /// Names the last added argument node, so that its value
+    /// can be retrieved with [Arguments::get](crate::Arguments::get).
+    /// Panics if the last node is not an argument.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        let node = self.nodes.pop().expect("Expected a node to name");
+        self.nodes.push(node.with_name(name));
+        self
+    }
+
+    /// Makes the last added literal or choice node match ignoring case.
+    /// Panics if the last node is not a literal or a choice.
+    pub fn ignore_case(mut self) -> Self {
+        let node = self.nodes.pop().expect("Expected a node to modify");
+        self.nodes.push(node.with_ignore_case());
+        self
+    }
+
     /// Finalizes the branch by adding a final node
     pub fn finalize(self, expects_empty_message: bool, value: T) -> CommandNode<T> {
         let final_node = CommandNode::final_node(expects_empty_message, value);
         fold_nodes(final_node, self.nodes.into_iter().rev())
     }
 
+    /// Finalizes the branch by redirecting it to the named subtree,
+    /// see [Subtrees](crate::Subtrees)
+    pub fn redirect(self, target: impl Into<String>) -> CommandNode<T> {
+        fold_nodes(CommandNode::redirect(target), self.nodes.into_iter().rev())
+    }
+
     /// Split the branch into several. Useful when several commands
     /// start from the same pattern.
     /// Assumes that at least one node has been inserted before
@@ -80,7 +180,7 @@ fn fold_nodes<T: Clone>(
     nodes: impl IntoIterator<Item = CommandNode<T>>,
 ) -> CommandNode<T> {
     (std::iter::once(final_node))
-        .chain(nodes.into_iter())
+        .chain(nodes)
         .reduce(|child, mut parent| {
             let children = parent
                 .children_mut()
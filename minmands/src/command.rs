@@ -0,0 +1,30 @@
+use crate::{Arguments, CommandNode};
+
+/// A type whose values are produced by command trees.
+/// Usually implemented with `#[derive(Command)]` (requires the `derive` feature):
+///
+/// ```ignore
+/// #[derive(Debug, Clone, minmands::Command)]
+/// enum Action {
+///     /// Matches `!roll` or `!roll 20`.
+///     #[command("!roll")]
+///     Roll {
+///         #[argument(int(1, 100), default = 6)]
+///         sides: i64,
+///     },
+///     /// Argument type is inferred from the field type: `String` is a word.
+///     #[command("!hello", "!hi")]
+///     Hello { name: String },
+///     /// Variants without `#[command]` have no tree.
+///     Say(String),
+/// }
+/// ```
+pub trait Command: Clone + Sized {
+    /// Command trees for every variant. Final nodes hold placeholder values,
+    /// which have to be filled with [Command::with_arguments].
+    fn command_nodes() -> Vec<CommandNode<Self>>;
+
+    /// Fills the fields of the placeholder value from the parsed arguments.
+    /// Returns `None` if some argument is missing or has an unexpected type.
+    fn with_arguments(self, arguments: &Arguments) -> Option<Self>;
+}
@@ -0,0 +1,183 @@
+use crate::{fold_case, strip_option, ArgumentType, CommandNode};
+
+/// Possible continuations of a partially typed command.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Completion {
+    /// Byte offset in the input where the word being completed starts.
+    pub offset: usize,
+    /// Literals and choices that can replace the word being completed.
+    pub candidates: Vec<String>,
+    /// Types of the arguments that are expected in place of the word being completed.
+    pub expected: Vec<ArgumentType>,
+}
+
+impl Completion {
+    /// Creates an empty completion for the input.
+    pub fn new(input: &str) -> Self {
+        let offset = input
+            .rfind(char::is_whitespace)
+            .map(|i| i + input[i..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0);
+        Self {
+            offset,
+            candidates: Vec::new(),
+            expected: Vec::new(),
+        }
+    }
+
+    /// Combines candidates from two completions of the same input.
+    pub fn merge(&mut self, other: Completion) {
+        for candidate in other.candidates {
+            if !self.candidates.contains(&candidate) {
+                self.candidates.push(candidate);
+            }
+        }
+        for argument_type in other.expected {
+            self.push_expected(argument_type);
+        }
+    }
+
+    fn push_expected(&mut self, argument_type: ArgumentType) {
+        if !self.expected.contains(&argument_type) {
+            self.expected.push(argument_type);
+        }
+    }
+}
+
+impl<T: Clone> CommandNode<T> {
+    /// Finds possible continuations of the last word in the input.
+    pub fn complete(&self, input: &str) -> Completion {
+        let mut completion = Completion::new(input);
+        self.complete_impl(input, input.trim_start(), &mut completion);
+        completion
+    }
+
+    /// `input` is the whole input, and `message` is the part of it that is left to parse.
+    fn complete_impl(&self, input: &str, message: &str, completion: &mut Completion) {
+        // Whether the message is the last word that is being completed
+        let is_last_word = input.len() - message.len() >= completion.offset;
+
+        match self {
+            CommandNode::Literal {
+                literals: options,
+                ignore_case,
+                child_nodes,
+            }
+            | CommandNode::ArgumentChoice {
+                choices: options,
+                ignore_case,
+                child_nodes,
+                ..
+            } => {
+                let is_prefix = |option: &str| {
+                    if *ignore_case {
+                        fold_case(option).starts_with(&fold_case(message))
+                    } else {
+                        option.starts_with(message)
+                    }
+                };
+                for option in options {
+                    match strip_option(option, message, *ignore_case) {
+                        Some(rest) if !rest.is_empty() => {
+                            complete_children(child_nodes, input, rest.trim_start(), completion);
+                        }
+                        _ => {
+                            if is_last_word
+                                && is_prefix(option)
+                                && !completion.candidates.contains(option)
+                            {
+                                completion.candidates.push(option.clone());
+                            }
+                        }
+                    }
+                }
+            }
+            CommandNode::Argument {
+                argument_type,
+                child_nodes,
+                ..
+            } => {
+                complete_argument(argument_type, child_nodes, input, message, completion);
+            }
+            CommandNode::Optional {
+                argument_type,
+                child_nodes,
+                ..
+            } => {
+                complete_argument(argument_type, child_nodes, input, message, completion);
+                complete_children(child_nodes, input, message, completion);
+            }
+            CommandNode::Redirect { .. } => {
+                complete_children(self.children(), input, message, completion);
+            }
+            CommandNode::Final { .. } => {}
+        }
+    }
+}
+
+fn complete_children<T: Clone>(
+    child_nodes: &[CommandNode<T>],
+    input: &str,
+    message: &str,
+    completion: &mut Completion,
+) {
+    for child_node in child_nodes {
+        child_node.complete_impl(input, message, completion);
+    }
+}
+
+fn complete_argument<T: Clone>(
+    argument_type: &ArgumentType,
+    child_nodes: &[CommandNode<T>],
+    input: &str,
+    message: &str,
+    completion: &mut Completion,
+) {
+    match argument_type.parse(message) {
+        Ok((_, argument)) if argument.len() < message.len() => {
+            // The argument is complete, continue with the rest
+            let message = message[argument.len()..].trim_start();
+            complete_children(child_nodes, input, message, completion);
+        }
+        Ok(_) => completion.push_expected(argument_type.clone()),
+        Err(_) => {
+            // Either the argument is still being typed or it is invalid
+            if !message.contains(char::is_whitespace) {
+                completion.push_expected(argument_type.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_complete() {
+        let node = CommandBuilder::new().literal(["!backup"]).split([
+            command!("create" | "load"; true, ()),
+            command!("delete"; int; true, ()),
+        ]);
+
+        let completion = node.complete("!ba");
+        assert_eq!(completion.offset, 0);
+        assert_eq!(completion.candidates, vec!["!backup"]);
+
+        let completion = node.complete("!backup ");
+        assert_eq!(completion.offset, 8);
+        assert_eq!(completion.candidates, vec!["create", "load", "delete"]);
+
+        let completion = node.complete("!backup de");
+        assert_eq!(completion.candidates, vec!["delete"]);
+
+        let completion = node.complete("!backup delete 1");
+        assert_eq!(completion.offset, 15);
+        assert!(completion.candidates.is_empty());
+        assert_eq!(completion.expected.len(), 1);
+
+        let completion = node.complete("!hello ");
+        assert!(completion.candidates.is_empty());
+        assert!(completion.expected.is_empty());
+    }
+}
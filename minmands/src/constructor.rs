@@ -1,4 +1,4 @@
-use crate::{ArgumentType, CommandNode};
+use crate::{ArgumentType, ArgumentValue, CommandNode};
 
 impl<T: Clone> CommandNode<T> {
     pub fn literal(
@@ -7,12 +7,14 @@ impl<T: Clone> CommandNode<T> {
     ) -> Self {
         Self::Literal {
             literals: literals.into_iter().map(|literal| literal.into()).collect(),
+            ignore_case: false,
             child_nodes: children,
         }
     }
 
     pub fn argument(argument_type: ArgumentType, children: Vec<CommandNode<T>>) -> Self {
         Self::Argument {
+            name: None,
             argument_type,
             child_nodes: children,
         }
@@ -23,11 +25,60 @@ impl<T: Clone> CommandNode<T> {
         children: Vec<CommandNode<T>>,
     ) -> Self {
         Self::ArgumentChoice {
+            name: None,
             choices: choices.into_iter().map(|choice| choice.into()).collect(),
+            ignore_case: false,
             child_nodes: children,
         }
     }
 
+    pub fn optional(
+        argument_type: ArgumentType,
+        default: Option<ArgumentValue>,
+        children: Vec<CommandNode<T>>,
+    ) -> Self {
+        Self::Optional {
+            name: None,
+            argument_type,
+            default,
+            child_nodes: children,
+        }
+    }
+
+    /// Sets the name of an argument node, so that its value
+    /// can be retrieved with [Arguments::get](crate::Arguments::get).
+    pub fn with_name(mut self, new_name: impl Into<String>) -> Self {
+        match &mut self {
+            Self::Argument { name, .. }
+            | Self::ArgumentChoice { name, .. }
+            | Self::Optional { name, .. } => {
+                *name = Some(new_name.into());
+            }
+            _ => panic!("Only argument nodes can be named"),
+        }
+        self
+    }
+
+    /// Makes a literal or a choice node match ignoring case,
+    /// see [strip_option](crate::strip_option).
+    pub fn with_ignore_case(mut self) -> Self {
+        match &mut self {
+            Self::Literal { ignore_case, .. } | Self::ArgumentChoice { ignore_case, .. } => {
+                *ignore_case = true;
+            }
+            _ => panic!("Only literal and choice nodes can ignore case"),
+        }
+        self
+    }
+
+    /// Creates an unlinked redirect to the named subtree, see [CommandNode::link].
+    pub fn redirect(target: impl Into<String>) -> Self {
+        Self::Redirect {
+            target: target.into(),
+            subtree: None,
+        }
+    }
+
     pub fn final_node(expects_empty_message: bool, value: T) -> Self {
         Self::Final {
             expects_empty_message,
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use crate::{edit_distance, fold_case, max_typos, CommandNode};
+
+/// Finds the trees that may match a message in one pass over it,
+/// instead of parsing the message with every tree.
+///
+/// The first literals of all trees are merged into a prefix trie.
+/// Trees that do not start with a literal are always considered candidates.
+/// Trees are referred to by their index in the list the dispatcher was built from.
+#[derive(Debug, Clone, Default)]
+pub struct Dispatcher {
+    /// Literals matched exactly.
+    exact: TrieNode,
+    /// Literals matched ignoring case, stored case folded.
+    folded: TrieNode,
+    /// Trees that do not start with a literal.
+    always: Vec<usize>,
+    /// First literals of the trees, whether they ignore case, and their trees.
+    literals: Vec<(String, bool, usize)>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: BTreeMap<char, TrieNode>,
+    /// Trees with a literal ending at this node.
+    trees: Vec<usize>,
+}
+
+impl TrieNode {
+    fn insert(&mut self, literal: impl IntoIterator<Item = char>, tree: usize) {
+        let node = literal
+            .into_iter()
+            .fold(self, |node, c| node.children.entry(c).or_default());
+        if !node.trees.contains(&tree) {
+            node.trees.push(tree);
+        }
+    }
+}
+
+impl Dispatcher {
+    pub fn new<'a, T: Clone + 'a>(trees: impl IntoIterator<Item = &'a CommandNode<T>>) -> Self {
+        let mut dispatcher = Self::default();
+        for (index, tree) in trees.into_iter().enumerate() {
+            match tree {
+                CommandNode::Literal {
+                    literals,
+                    ignore_case,
+                    ..
+                } => {
+                    for literal in literals {
+                        dispatcher
+                            .literals
+                            .push((literal.clone(), *ignore_case, index));
+                        if *ignore_case {
+                            dispatcher.folded.insert(fold_case(literal).chars(), index);
+                        } else {
+                            dispatcher.exact.insert(literal.chars(), index);
+                        }
+                    }
+                }
+                _ => dispatcher.always.push(index),
+            }
+        }
+        dispatcher
+    }
+
+    /// Indices of the trees whose first literal matches the start of the message,
+    /// in ascending order. Only these trees can successfully parse the message.
+    pub fn candidates(&self, message: &str) -> Vec<usize> {
+        let mut candidates = self.always.clone();
+        collect_matches(&self.exact, message, false, &mut candidates);
+        collect_matches(&self.folded, message, true, &mut candidates);
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+
+    /// The first literal closest to the first word of the message,
+    /// if the word looks like a typo of it, among the trees accepted by the `filter`.
+    pub fn suggest(&self, message: &str, filter: impl Fn(usize) -> bool) -> Option<&str> {
+        let word = message.split_whitespace().next()?;
+        self.literals
+            .iter()
+            .filter(|(.., tree)| filter(*tree))
+            .map(|(literal, ignore_case, _)| {
+                let distance = if *ignore_case {
+                    edit_distance(&fold_case(literal), &fold_case(word))
+                } else {
+                    edit_distance(literal, word)
+                };
+                (literal, distance)
+            })
+            .filter(|(literal, distance)| *distance > 0 && *distance <= max_typos(literal))
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(literal, _)| literal.as_str())
+    }
+}
+
+/// Walks the trie along the message and collects the trees of every literal
+/// that is followed by whitespace or the end of the message, see [crate::strip_option].
+fn collect_matches(trie: &TrieNode, message: &str, ignore_case: bool, trees: &mut Vec<usize>) {
+    let mut node = trie;
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = if ignore_case {
+            fold_case(c.encode_utf8(&mut [0; 4]))
+                .chars()
+                .try_fold(node, |node, c| node.children.get(&c))
+        } else {
+            node.children.get(&c)
+        };
+        let Some(next) = next else {
+            return;
+        };
+        node = next;
+        if chars.peek().is_none_or(|c| c.is_whitespace()) {
+            trees.extend(&node.trees);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_dispatch() {
+        let trees = [
+            command!("!hi", "!hello"; true, ()),
+            command!("!hill"; word; true, ()),
+            command!(word; true, ()),
+            command!("!hi there"; true, ()),
+            CommandBuilder::new()
+                .literal(["!straße"])
+                .ignore_case()
+                .finalize(true, ()),
+        ];
+        let dispatcher = Dispatcher::new(&trees);
+
+        assert_eq!(dispatcher.candidates("!hi"), vec![0, 2]);
+        assert_eq!(dispatcher.candidates("!hill bob"), vec![1, 2]);
+        assert_eq!(dispatcher.candidates("!hi there"), vec![0, 2, 3]);
+        assert_eq!(dispatcher.candidates("!hix"), vec![2]);
+        assert_eq!(dispatcher.candidates("!STRASSE"), vec![2, 4]);
+        assert_eq!(dispatcher.candidates("hello"), vec![2]);
+
+        assert_eq!(dispatcher.suggest("!hil bob", |_| true), Some("!hi"));
+        assert_eq!(
+            dispatcher.suggest("!hil bob", |tree| tree != 0),
+            Some("!hill")
+        );
+        assert_eq!(dispatcher.suggest("!STRASE", |_| true), Some("!straße"));
+        assert_eq!(dispatcher.suggest("!hi", |_| true), None);
+        assert_eq!(dispatcher.suggest("!unknown", |_| true), None);
+    }
+}
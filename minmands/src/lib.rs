@@ -1,7 +1,25 @@
+mod argument;
 mod builder;
+mod command;
+mod complete;
 mod constructor;
+mod dispatch;
 mod macros;
+mod redirect;
 mod tree;
+mod usage;
+mod validate;
+mod value;
 
+pub use argument::*;
 pub use builder::*;
+pub use command::*;
+pub use complete::*;
+pub use dispatch::*;
+pub use redirect::*;
 pub use tree::*;
+pub use validate::*;
+pub use value::*;
+
+#[cfg(feature = "derive")]
+pub use minmands_derive::Command;
@@ -1,28 +1,118 @@
 #[macro_export]
 macro_rules! command {
-    // Final
-    ($empty:expr, $value:expr) => {{
-        $crate::CommandNode::final_node($empty, $value)
-    }};
+    // Argument types, used by optional arguments
+    (@type word) => { $crate::ArgumentType::Word };
+    (@type line) => { $crate::ArgumentType::Line };
+    (@type quoted) => { $crate::ArgumentType::Quoted };
+    (@type int($min:expr, $max:expr)) => {
+        $crate::ArgumentType::Integer {
+            min: Some($min),
+            max: Some($max),
+        }
+    };
+    (@type int) => {
+        $crate::ArgumentType::Integer {
+            min: None,
+            max: None,
+        }
+    };
+    (@type float) => { $crate::ArgumentType::Float };
+    (@type duration) => { $crate::ArgumentType::Duration };
+    (@type user) => { $crate::ArgumentType::User };
+    (@type regex($pattern:expr)) => {
+        $crate::ArgumentType::Regex(
+            $crate::Pattern::new($pattern).expect("Invalid regex pattern"),
+        )
+    };
     // Literal
     ($($literals:literal),+; $($tail:tt)*) => {{
         let children = vec![$crate::command!($($tail)*)];
         $crate::CommandNode::literal([$($literals),+], children)
     }};
     // Choice
-    ($($choices:literal)|+; $($tail:tt)*) => {{
+    ($($choices:literal)|+ $(as $name:literal)?; $($tail:tt)*) => {{
         let children = vec![$crate::command!($($tail)*)];
         $crate::CommandNode::argument_choice([$($choices),+], children)
+            $(.with_name($name))?
     }};
     // Argument word
-    (word; $($tail:tt)*) => {{
+    (word $(as $name:literal)?; $($tail:tt)*) => {{
         let children = vec![$crate::command!($($tail)*)];
         $crate::CommandNode::argument($crate::ArgumentType::Word, children)
+            $(.with_name($name))?
     }};
     // Argument line
-    (line; $($tail:tt)*) => {{
+    (line $(as $name:literal)?; $($tail:tt)*) => {{
         let children = vec![$crate::command!($($tail)*)];
         $crate::CommandNode::argument($crate::ArgumentType::Line, children)
+            $(.with_name($name))?
+    }};
+    // Argument quoted
+    (quoted $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        $crate::CommandNode::argument($crate::ArgumentType::Quoted, children)
+            $(.with_name($name))?
+    }};
+    // Argument integer in range
+    (int($min:expr, $max:expr) $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        let argument_type = $crate::ArgumentType::Integer {
+            min: Some($min),
+            max: Some($max),
+        };
+        $crate::CommandNode::argument(argument_type, children)
+            $(.with_name($name))?
+    }};
+    // Argument integer
+    (int $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        let argument_type = $crate::ArgumentType::Integer {
+            min: None,
+            max: None,
+        };
+        $crate::CommandNode::argument(argument_type, children)
+            $(.with_name($name))?
+    }};
+    // Argument float
+    (float $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        $crate::CommandNode::argument($crate::ArgumentType::Float, children)
+            $(.with_name($name))?
+    }};
+    // Argument duration
+    (duration $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        $crate::CommandNode::argument($crate::ArgumentType::Duration, children)
+            $(.with_name($name))?
+    }};
+    // Argument user
+    (user $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        $crate::CommandNode::argument($crate::ArgumentType::User, children)
+            $(.with_name($name))?
+    }};
+    // Argument matching a regex
+    (regex($pattern:expr) $(as $name:literal)?; $($tail:tt)*) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        let argument_type = $crate::command!(@type regex($pattern));
+        $crate::CommandNode::argument(argument_type, children)
+            $(.with_name($name))?
+    }};
+    // Optional argument
+    (
+        [$type:ident $(($($params:tt)*))? $(as $name:literal)?] $(= $default:expr)?;
+        $($tail:tt)*
+    ) => {{
+        let children = vec![$crate::command!($($tail)*)];
+        let argument_type = $crate::command!(@type $type $(($($params)*))?);
+        let default = None $(.or(Some($crate::ArgumentValue::from($default))))?;
+        $crate::CommandNode::optional(argument_type, default, children)
+            $(.with_name($name))?
+    }};
+    // Final
+    // Goes last, so that argument names (`word as "name"`) are not mistaken for casts
+    ($empty:expr, $value:expr) => {{
+        $crate::CommandNode::final_node($empty, $value)
     }};
 }
 
@@ -59,5 +149,70 @@ mod tests {
             line;
             true, ()
         );
+
+        // `CommandBuilder::new().literal(["!so"]).user().finalize(...)`
+        command!(
+            "!so";
+            user;
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!color"]).regex("#[0-9a-fA-F]{6}").finalize(...)`
+        command!(
+            "!color";
+            regex("#[0-9a-fA-F]{6}") as "color";
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!addquote"]).quoted().word().finalize(...)`
+        command!(
+            "!addquote";
+            quoted;
+            word;
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!roll"]).integer(Some(1), Some(100)).finalize(...)`
+        command!(
+            "!roll";
+            int(1, 100);
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!timeout"]).word().duration().finalize(...)`
+        command!(
+            "!timeout";
+            word;
+            duration;
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!backup"]).choice(["create", "load"]).named("action").finalize(...)`
+        command!(
+            "!backup";
+            "create" | "load" as "action";
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!hello"]).optional(ArgumentType::Word).named("name").finalize(...)`
+        command!(
+            "!hello";
+            [word as "name"];
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!roll"]).optional_or(ArgumentType::Integer { .. }, 6).finalize(...)`
+        command!(
+            "!roll";
+            [int(1, 100)] = 6;
+            true, ()
+        );
+
+        // `CommandBuilder::new().literal(["!scale"]).float().finalize(...)`
+        command!(
+            "!scale";
+            float;
+            true, ()
+        );
     }
 }
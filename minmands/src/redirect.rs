@@ -0,0 +1,256 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::CommandNode;
+
+/// Named subtrees that [CommandNode::Redirect] nodes can point to.
+///
+/// ```
+/// # use minmands::*;
+/// let mut subtrees = Subtrees::new();
+/// subtrees.insert(
+///     "quote",
+///     vec![
+///         command!("add"; line; true, ()),
+///         command!("del" | "get"; int; true, ()),
+///     ],
+/// );
+/// let subtrees = subtrees.link().unwrap();
+///
+/// let mut node = CommandBuilder::new().literal(["!quote", "!q"]).redirect("quote");
+/// node.link(&subtrees).unwrap();
+/// assert!(node.parse("!q get 5").is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Subtrees<T: Clone> {
+    definitions: BTreeMap<String, Vec<CommandNode<T>>>,
+}
+
+/// Subtrees with all redirects resolved, see [Subtrees::link].
+#[derive(Debug, Clone)]
+pub struct LinkedSubtrees<T: Clone> {
+    subtrees: BTreeMap<String, Arc<[CommandNode<T>]>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedirectError {
+    /// There is no subtree with that name.
+    UnknownTarget(String),
+    /// Subtrees redirect to each other in a loop, listed in the order they redirect.
+    Cycle(Vec<String>),
+}
+
+impl std::error::Error for RedirectError {}
+
+impl std::fmt::Display for RedirectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RedirectError::UnknownTarget(target) => {
+                write!(f, "Redirect to an unknown subtree {target:?}")
+            }
+            RedirectError::Cycle(cycle) => {
+                write!(f, "Subtrees redirect in a cycle: {}", cycle.join(" -> "))
+            }
+        }
+    }
+}
+
+impl<T: Clone> Subtrees<T> {
+    pub fn new() -> Self {
+        Self {
+            definitions: BTreeMap::new(),
+        }
+    }
+
+    /// Defines a subtree. The nodes are tried in order, like the children of a node.
+    pub fn insert(&mut self, name: impl Into<String>, nodes: Vec<CommandNode<T>>) {
+        self.definitions.insert(name.into(), nodes);
+    }
+
+    /// Resolves redirects inside the subtrees.
+    /// Fails if a redirect points to an unknown subtree or if subtrees redirect in a cycle.
+    pub fn link(&self) -> Result<LinkedSubtrees<T>, RedirectError> {
+        let (linked, errors) = self.link_partial();
+        match errors.into_iter().next() {
+            Some((_, err)) => Err(err),
+            None => Ok(linked),
+        }
+    }
+
+    /// Resolves redirects inside the subtrees, like [Subtrees::link],
+    /// but leaves out only the subtrees that fail to link, instead of failing altogether.
+    /// Returns the names of the left out subtrees with the reasons.
+    pub fn link_partial(&self) -> (LinkedSubtrees<T>, Vec<(String, RedirectError)>) {
+        let mut linked = LinkedSubtrees::default();
+        let mut errors = Vec::new();
+        for name in self.definitions.keys() {
+            if let Err(err) = self.resolve(name, &mut Vec::new(), &mut linked) {
+                errors.push((name.clone(), err));
+            }
+        }
+        (linked, errors)
+    }
+
+    /// Links the subtree after all subtrees it redirects to.
+    /// `stack` contains the subtrees that are being linked.
+    fn resolve(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+        linked: &mut LinkedSubtrees<T>,
+    ) -> Result<Arc<[CommandNode<T>]>, RedirectError> {
+        if let Some(subtree) = linked.subtrees.get(name) {
+            return Ok(subtree.clone());
+        }
+        if let Some(start) = stack.iter().position(|visited| visited == name) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(name.to_owned());
+            return Err(RedirectError::Cycle(cycle));
+        }
+        let mut nodes = self
+            .definitions
+            .get(name)
+            .ok_or_else(|| RedirectError::UnknownTarget(name.to_owned()))?
+            .clone();
+
+        stack.push(name.to_owned());
+        for node in &mut nodes {
+            node.link_with(&mut |target| self.resolve(target, stack, linked))?;
+        }
+        stack.pop();
+
+        let subtree: Arc<[CommandNode<T>]> = nodes.into();
+        linked.subtrees.insert(name.to_owned(), subtree.clone());
+        Ok(subtree)
+    }
+}
+
+impl<T: Clone> Default for Subtrees<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Default for LinkedSubtrees<T> {
+    fn default() -> Self {
+        Self {
+            subtrees: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Clone> LinkedSubtrees<T> {
+    pub fn get(&self, name: &str) -> Option<&Arc<[CommandNode<T>]>> {
+        self.subtrees.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Arc<[CommandNode<T>]>)> {
+        self.subtrees.iter()
+    }
+}
+
+impl<T: Clone> CommandNode<T> {
+    /// Points all redirects in the tree to the linked subtrees.
+    /// The subtrees are shared, not cloned.
+    pub fn link(&mut self, subtrees: &LinkedSubtrees<T>) -> Result<(), RedirectError> {
+        self.link_with(&mut |target| {
+            subtrees
+                .get(target)
+                .cloned()
+                .ok_or_else(|| RedirectError::UnknownTarget(target.to_owned()))
+        })
+    }
+
+    fn link_with(
+        &mut self,
+        resolve: &mut impl FnMut(&str) -> Result<Arc<[CommandNode<T>]>, RedirectError>,
+    ) -> Result<(), RedirectError> {
+        if let CommandNode::Redirect { target, subtree } = self {
+            *subtree = Some(resolve(target)?);
+            return Ok(());
+        }
+        if let Some(child_nodes) = self.children_mut() {
+            for child_node in child_nodes {
+                child_node.link_with(resolve)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_redirect() {
+        let mut subtrees = Subtrees::new();
+        subtrees.insert("amount", vec![command!(int as "amount"; true, ())]);
+        subtrees.insert(
+            "give",
+            vec![CommandBuilder::new()
+                .word()
+                .named("user")
+                .redirect("amount")],
+        );
+        let linked = subtrees.link().unwrap();
+
+        let mut node = CommandBuilder::new()
+            .literal(["!give", "!g"])
+            .redirect("give");
+        node.link(&linked).unwrap();
+        let parsed = node.parse("!g bob 10").unwrap();
+        assert_eq!(parsed.arguments.get::<i64>("amount"), Some(10));
+        assert_eq!(node.usage(), vec!["!give|!g <user> <amount>"]);
+        assert_eq!(node.complete("!give bob ").expected.len(), 1);
+
+        let mut node = CommandBuilder::new().literal(["!take"]).redirect("take");
+        assert!(node.parse("!take").is_err());
+        assert_eq!(
+            node.link(&linked),
+            Err(RedirectError::UnknownTarget("take".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_redirect_cycle() {
+        let mut subtrees = Subtrees::<()>::new();
+        subtrees.insert("a", vec![CommandBuilder::new().word().redirect("b")]);
+        subtrees.insert(
+            "b",
+            vec![command!("x"; true, ()), CommandNode::redirect("a")],
+        );
+        assert_eq!(
+            subtrees.link().unwrap_err(),
+            RedirectError::Cycle(vec!["a".to_owned(), "b".to_owned(), "a".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_link_partial() {
+        let mut subtrees = Subtrees::new();
+        subtrees.insert("amount", vec![command!(int as "amount"; true, ())]);
+        subtrees.insert(
+            "give",
+            vec![CommandBuilder::new().word().redirect("amount")],
+        );
+        subtrees.insert("take", vec![CommandBuilder::new().word().redirect("typo")]);
+        subtrees.insert("steal", vec![CommandNode::redirect("take")]);
+
+        let (linked, errors) = subtrees.link_partial();
+        let names: Vec<&String> = linked.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["amount", "give"]);
+        assert_eq!(
+            errors,
+            [
+                (
+                    "steal".to_owned(),
+                    RedirectError::UnknownTarget("typo".to_owned())
+                ),
+                (
+                    "take".to_owned(),
+                    RedirectError::UnknownTarget("typo".to_owned())
+                ),
+            ]
+        );
+    }
+}
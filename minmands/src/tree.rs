@@ -1,77 +1,137 @@
-#[derive(Debug, Clone, Copy)]
-pub enum ArgumentType {
-    /// Parse a single word.
-    Word,
-    /// Parse till the end of line.
-    Line,
-    /// Parse till the end of input.
-    Tail,
-}
+use std::sync::Arc;
 
-impl std::fmt::Display for ArgumentType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ArgumentType::Word => write!(f, "Word"),
-            ArgumentType::Line => write!(f, "Line"),
-            ArgumentType::Tail => write!(f, "Tail"),
-        }
-    }
-}
+use serde::{Deserialize, Serialize};
+
+use crate::{ArgumentType, ArgumentValue, Arguments};
 
 /// A node in the command tree.
 /// Generic over the 'marker' type that is returned upon succefully parsing a branch.
-#[derive(Debug, Clone)]
+///
+/// Serialized as a table with a `type` field naming the variant in snake_case, e.g.
+/// `{ type = "literal", literals = ["!hello"], child_nodes = [...] }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum CommandNode<T: Clone> {
+    /// Matches one of the literals, followed by whitespace or the end of the message.
     Literal {
         literals: Vec<String>,
+        /// Whether to match the literals ignoring case, see [strip_option].
+        #[serde(default)]
+        ignore_case: bool,
+        #[serde(default)]
         child_nodes: Vec<CommandNode<T>>,
     },
     Argument {
+        /// Name used to look up the parsed value, see [Arguments::get].
+        #[serde(default)]
+        name: Option<String>,
         argument_type: ArgumentType,
+        #[serde(default)]
         child_nodes: Vec<CommandNode<T>>,
     },
+    #[serde(rename = "choice")]
     ArgumentChoice {
+        /// Name used to look up the parsed value, see [Arguments::get].
+        #[serde(default)]
+        name: Option<String>,
+        /// Matched the same way as literals, see [CommandNode::Literal].
         choices: Vec<String>,
+        /// Whether to match the choices ignoring case, see [strip_option].
+        #[serde(default)]
+        ignore_case: bool,
+        #[serde(default)]
         child_nodes: Vec<CommandNode<T>>,
     },
+    /// An argument that may be omitted. When the argument cannot be parsed,
+    /// the `default` value (or [ArgumentValue::Empty] if there is none)
+    /// is used instead, and parsing continues with the children.
+    Optional {
+        /// Name used to look up the parsed value, see [Arguments::get].
+        #[serde(default)]
+        name: Option<String>,
+        argument_type: ArgumentType,
+        #[serde(default)]
+        default: Option<ArgumentValue>,
+        #[serde(default)]
+        child_nodes: Vec<CommandNode<T>>,
+    },
+    /// Continues parsing with a named subtree, see [Subtrees](crate::Subtrees).
+    /// Lets several branches share the same grammar without cloning it.
+    Redirect {
+        /// Name of the subtree.
+        target: String,
+        /// The subtree, set by [CommandNode::link].
+        #[serde(skip)]
+        subtree: Option<Arc<[CommandNode<T>]>>,
+    },
     Final {
         /// If `true`, then this node will actiate only when the message is fully consumed,
         /// when it reached this node. If `false`, then this node will always activate if reached.
+        #[serde(default = "default_expects_empty_message")]
         expects_empty_message: bool,
         value: T,
     },
 }
 
-macro_rules! child_nodes {
-    ( $child_nodes: expr, $message: expr, $arguments: expr ) => {
-        for child_node in $child_nodes {
-            if let Ok(arguments) = child_node.parse_impl($message, $arguments.clone()) {
-                return Ok(arguments);
-            }
-        }
-    };
+fn default_expects_empty_message() -> bool {
+    true
 }
 
 #[derive(Debug, Clone)]
 pub struct ParsedCommand<T> {
     /// The 'marker' value.
     pub value: T,
-    pub arguments: Vec<String>,
+    pub arguments: Arguments,
 }
 
 #[derive(Debug, Clone)]
 pub struct ParseError {
     /// Parsed arguments.
-    pub parsed: Vec<String>,
+    pub parsed: Arguments,
+    /// Byte offset in the message where parsing failed.
+    pub offset: usize,
     /// Error message.
     pub msg: String,
+    /// The closest literal or choice, if the input looks like a typo of one.
+    pub suggestion: Option<String>,
+}
+
+impl ParseError {
+    /// Whether the error is more helpful to report than the `other` one,
+    /// when both come from parsing the same `message`:
+    /// it got further into the message, or it suggests a closer fix at the same point.
+    pub fn is_better_than(&self, other: &ParseError, message: &str) -> bool {
+        match self.offset.cmp(&other.offset) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => match (self.typos(message), other.typos(message)) {
+                (Some(typos), Some(other)) => typos < other,
+                (Some(_), None) => true,
+                (None, _) => false,
+            },
+        }
+    }
+
+    /// Edit distance between the word at the error and the suggestion, if there is one.
+    fn typos(&self, message: &str) -> Option<usize> {
+        let suggestion = self.suggestion.as_ref()?;
+        let word = message
+            .get(self.offset..)
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap_or_default();
+        Some(edit_distance(suggestion, word))
+    }
 }
 
 impl std::error::Error for ParseError {}
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Parse error: {}", self.msg)
+        write!(f, "Parse error at {}: {}", self.offset, self.msg)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ". Did you mean {suggestion:?}?")?;
+        }
+        Ok(())
     }
 }
 
@@ -80,119 +140,116 @@ type ParseResult<T> = Result<ParsedCommand<T>, ParseError>;
 impl<T: Clone> CommandNode<T> {
     /// Attempts to parse the command.
     /// On success, returns the list of parsed arguments.
+    /// On failure, returns the most helpful error among the branches, see [ParseError::is_better_than].
     pub fn parse(&self, message: &str) -> ParseResult<T> {
-        self.parse_impl(message, Vec::new())
+        self.parse_impl(message, message, Arguments::new())
     }
 
-    fn parse_impl(&self, message: &str, mut arguments: Vec<String>) -> ParseResult<T> {
+    /// `input` is the whole message, and `message` is the part of it that is left to parse.
+    fn parse_impl(&self, input: &str, message: &str, mut arguments: Arguments) -> ParseResult<T> {
+        let error = |arguments: &Arguments, msg: String| ParseError {
+            parsed: arguments.clone(),
+            offset: message.as_ptr() as usize - input.as_ptr() as usize,
+            msg,
+            suggestion: None,
+        };
+
         match self {
             CommandNode::Literal {
                 literals,
+                ignore_case,
                 child_nodes,
-            } => literals
-                .iter()
-                .find(|&literal| message.starts_with(literal))
-                .ok_or_else(|| ParseError {
-                    parsed: arguments.clone(),
-                    msg: {
-                        let mut msg = format!("Expected one of: ");
-                        let mut literals = literals.iter();
-                        if let Some(literal) = literals.next() {
-                            msg += literal;
-                        }
-                        for literal in literals {
-                            msg += ", ";
-                            msg += literal;
-                        }
-                        msg += ". Found: \"";
-                        msg += message;
-                        msg += "\"";
-                        msg
-                    },
-                })
-                .and_then(|literal| {
-                    let message = message[literal.len()..].trim();
-                    child_nodes!(child_nodes, message, arguments);
-                    // TODO: better error
-                    Err(ParseError {
-                        parsed: arguments,
-                        msg: "No inner nodes matched".to_string(),
-                    })
-                }),
+            } => {
+                let message = literals
+                    .iter()
+                    .find_map(|literal| strip_option(literal, message, *ignore_case))
+                    .ok_or_else(|| ParseError {
+                        suggestion: suggest(literals, message),
+                        ..error(&arguments, expected_one_of(literals, message))
+                    })?;
+                parse_children(child_nodes, input, message.trim(), arguments)
+            }
 
             CommandNode::Argument {
+                name,
                 argument_type,
                 child_nodes,
-            } => match argument_type {
-                ArgumentType::Word => message.split_whitespace().next(),
-                ArgumentType::Line => message.lines().next(),
-                ArgumentType::Tail => {
-                    if message.trim().is_empty() {
-                        None
-                    } else {
-                        Some(message)
-                    }
-                }
-            }
-            .ok_or_else(|| ParseError {
-                parsed: arguments.clone(),
-                msg: format!("Expected a {} argument", argument_type),
-            })
-            .and_then(|argument| {
+            } => {
+                let (value, argument) = argument_type
+                    .parse(message)
+                    .map_err(|msg| error(&arguments, invalid_argument(name, &arguments, msg)))?;
                 let message = message[argument.len()..].trim();
-                arguments.push(argument.to_owned());
-                child_nodes!(child_nodes, message, arguments);
-                // TODO: better error
-                Err(ParseError {
-                    parsed: arguments,
-                    msg: "No inner nodes matched".to_string(),
-                })
-            }),
+                arguments.push(name.clone(), value);
+                parse_children(child_nodes, input, message, arguments)
+            }
 
             CommandNode::ArgumentChoice {
+                name,
                 choices,
+                ignore_case,
                 child_nodes,
-            } => choices
-                .iter()
-                .find(|choice| message.starts_with(*choice))
-                .ok_or_else(|| ParseError {
-                    parsed: arguments.clone(),
-                    msg: {
-                        let mut msg = format!("Expected one of: ");
-                        let mut literals = choices.iter();
-                        if let Some(literal) = literals.next() {
-                            msg += literal;
-                        }
-                        for literal in literals {
-                            msg += ", ";
-                            msg += literal;
+            } => {
+                let (choice, message) = choices
+                    .iter()
+                    .find_map(|choice| {
+                        strip_option(choice, message, *ignore_case).map(|rest| (choice, rest))
+                    })
+                    .ok_or_else(|| ParseError {
+                        suggestion: suggest(choices, message),
+                        ..error(&arguments, expected_one_of(choices, message))
+                    })?;
+                arguments.push(name.clone(), ArgumentValue::String(choice.to_owned()));
+                parse_children(child_nodes, input, message.trim(), arguments)
+            }
+
+            CommandNode::Optional {
+                name,
+                argument_type,
+                default,
+                child_nodes,
+            } => {
+                // Try the argument
+                let parsed_error = match argument_type.parse(message) {
+                    Ok((value, argument)) => {
+                        let message = message[argument.len()..].trim();
+                        let mut arguments = arguments.clone();
+                        arguments.push(name.clone(), value);
+                        match parse_children(child_nodes, input, message, arguments) {
+                            Ok(parsed) => return Ok(parsed),
+                            Err(err) => Some(err),
                         }
-                        msg += ". Found: \"";
-                        msg += message;
-                        msg += "\"";
-                        msg
-                    },
+                    }
+                    Err(_) => None,
+                };
+
+                // Skip the argument
+                let value = default.clone().unwrap_or(ArgumentValue::Empty);
+                arguments.push(name.clone(), value);
+                parse_children(child_nodes, input, message, arguments).map_err(|err| {
+                    match parsed_error {
+                        Some(parsed_error) => best_error(parsed_error, err, input),
+                        None => err,
+                    }
                 })
-                .and_then(|choice| {
-                    let message = message[choice.len()..].trim();
-                    arguments.push(choice.to_owned());
-                    child_nodes!(child_nodes, message, arguments);
-                    // TODO: better error
-                    Err(ParseError {
-                        parsed: arguments,
-                        msg: "No inner nodes matched".to_string(),
-                    })
-                }),
+            }
+
+            CommandNode::Redirect { target, subtree } => match subtree {
+                Some(subtree) => parse_children(subtree, input, message, arguments),
+                None => Err(error(
+                    &arguments,
+                    format!("Redirect to {target:?} is not linked"),
+                )),
+            },
 
             CommandNode::Final {
                 expects_empty_message,
                 value,
             } => {
                 if *expects_empty_message && !message.trim().is_empty() {
-                    Err(ParseError {
-                        parsed: arguments,
-                        msg: format!("Did not expect any more arguments, found: {:?}", message),
-                    })
+                    Err(error(
+                        &arguments,
+                        format!("Did not expect any more arguments, found: {:?}", message),
+                    ))
                 } else {
                     Ok(ParsedCommand {
                         value: value.clone(),
@@ -203,12 +260,442 @@ impl<T: Clone> CommandNode<T> {
         }
     }
 
+    /// The 'marker' values of all final nodes in the tree.
+    pub fn final_values(&self) -> Vec<&T> {
+        match self {
+            CommandNode::Final { value, .. } => vec![value],
+            _ => self
+                .children()
+                .iter()
+                .flat_map(|child_node| child_node.final_values())
+                .collect(),
+        }
+    }
+
+    /// Converts the 'marker' values of all final nodes.
+    pub fn map<U: Clone>(self, f: &mut impl FnMut(T) -> U) -> CommandNode<U> {
+        let map_children = |child_nodes: Vec<CommandNode<T>>, f: &mut _| {
+            child_nodes
+                .into_iter()
+                .map(|child_node| child_node.map(f))
+                .collect()
+        };
+        match self {
+            CommandNode::Literal {
+                literals,
+                ignore_case,
+                child_nodes,
+            } => CommandNode::Literal {
+                literals,
+                ignore_case,
+                child_nodes: map_children(child_nodes, f),
+            },
+            CommandNode::Argument {
+                name,
+                argument_type,
+                child_nodes,
+            } => CommandNode::Argument {
+                name,
+                argument_type,
+                child_nodes: map_children(child_nodes, f),
+            },
+            CommandNode::ArgumentChoice {
+                name,
+                choices,
+                ignore_case,
+                child_nodes,
+            } => CommandNode::ArgumentChoice {
+                name,
+                choices,
+                ignore_case,
+                child_nodes: map_children(child_nodes, f),
+            },
+            CommandNode::Optional {
+                name,
+                argument_type,
+                default,
+                child_nodes,
+            } => CommandNode::Optional {
+                name,
+                argument_type,
+                default,
+                child_nodes: map_children(child_nodes, f),
+            },
+            CommandNode::Redirect { target, subtree } => CommandNode::Redirect {
+                target,
+                // The mapped subtree is not shared with other redirects anymore
+                subtree: subtree.map(|subtree| {
+                    subtree
+                        .iter()
+                        .map(|child_node| child_node.clone().map(f))
+                        .collect()
+                }),
+            },
+            CommandNode::Final {
+                expects_empty_message,
+                value,
+            } => CommandNode::Final {
+                expects_empty_message,
+                value: f(value),
+            },
+        }
+    }
+
+    /// Child nodes, empty for a final node.
+    /// For a redirect, the nodes of the linked subtree.
+    pub fn children(&self) -> &[CommandNode<T>] {
+        match self {
+            Self::Literal { child_nodes, .. }
+            | Self::Argument { child_nodes, .. }
+            | Self::ArgumentChoice { child_nodes, .. }
+            | Self::Optional { child_nodes, .. } => child_nodes,
+            Self::Redirect { subtree, .. } => subtree.as_deref().unwrap_or_default(),
+            Self::Final { .. } => &[],
+        }
+    }
+
     pub fn children_mut(&mut self) -> Option<&mut Vec<CommandNode<T>>> {
         match self {
             Self::Literal { child_nodes, .. } => Some(child_nodes),
             Self::Argument { child_nodes, .. } => Some(child_nodes),
             Self::ArgumentChoice { child_nodes, .. } => Some(child_nodes),
-            Self::Final { .. } => None,
+            Self::Optional { child_nodes, .. } => Some(child_nodes),
+            Self::Redirect { .. } | Self::Final { .. } => None,
+        }
+    }
+}
+
+/// Attempts to parse the children in order and returns the first success.
+/// If all children fail, returns the most helpful error.
+fn parse_children<T: Clone>(
+    child_nodes: &[CommandNode<T>],
+    input: &str,
+    message: &str,
+    arguments: Arguments,
+) -> ParseResult<T> {
+    let mut error: Option<ParseError> = None;
+    for child_node in child_nodes {
+        match child_node.parse_impl(input, message, arguments.clone()) {
+            Ok(parsed) => return Ok(parsed),
+            Err(err) => {
+                error = Some(match error {
+                    Some(error) => best_error(error, err, input),
+                    None => err,
+                });
+            }
+        }
+    }
+    Err(error.unwrap_or_else(|| ParseError {
+        parsed: arguments,
+        offset: message.as_ptr() as usize - input.as_ptr() as usize,
+        msg: "The branch has no final node".to_string(),
+        suggestion: None,
+    }))
+}
+
+/// Matches the option at the start of the message and returns the rest of the message.
+/// The option has to be followed by whitespace or the end of the message,
+/// so `!reload` does not match `!reloadXYZ`.
+///
+/// When `ignore_case` is set, both strings are compared after Unicode case folding,
+/// so `!Hello` matches `!hello`, and `STRASSE` matches `straße`.
+pub fn strip_option<'a>(option: &str, message: &'a str, ignore_case: bool) -> Option<&'a str> {
+    let rest = if ignore_case {
+        let option = fold_case(option);
+        let mut folded = String::new();
+        let mut rest = option.is_empty().then_some(message);
+        for (i, c) in message.char_indices() {
+            if rest.is_some() {
+                break;
+            }
+            folded.extend(fold_char(c));
+            if folded == option {
+                rest = Some(&message[i + c.len_utf8()..]);
+            } else if !option.starts_with(&folded) {
+                return None;
+            }
         }
+        rest?
+    } else {
+        message.strip_prefix(option)?
+    };
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
+}
+
+/// Case folds the string for caseless comparison, see [fold_char].
+pub(crate) fn fold_case(text: &str) -> String {
+    text.chars().flat_map(fold_char).collect()
+}
+
+/// Unicode full case folding for the most part: lowercase mapping,
+/// plus the characters whose folding differs from their lowercase.
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    let folded: Option<&str> = match c {
+        'ß' | 'ẞ' => Some("ss"),
+        'ς' => Some("σ"),
+        'ſ' => Some("s"),
+        'ﬀ' => Some("ff"),
+        'ﬁ' => Some("fi"),
+        'ﬂ' => Some("fl"),
+        _ => None,
+    };
+    let lowercase = folded.is_none().then(|| c.to_lowercase());
+    folded
+        .into_iter()
+        .flat_map(str::chars)
+        .chain(lowercase.into_iter().flatten())
+}
+
+/// Picks the more helpful error (see [ParseError::is_better_than]),
+/// preferring the first one on ties.
+fn best_error(first: ParseError, second: ParseError, input: &str) -> ParseError {
+    if second.is_better_than(&first, input) {
+        second
+    } else {
+        first
+    }
+}
+
+fn expected_one_of(options: &[String], message: &str) -> String {
+    format!(
+        "Expected one of: {}. Found: {:?}",
+        options.join(", "),
+        message
+    )
+}
+
+fn invalid_argument(name: &Option<String>, arguments: &Arguments, msg: String) -> String {
+    match name {
+        Some(name) => format!("Argument {name:?} is invalid: {msg}"),
+        None => format!("Argument #{} is invalid: {msg}", arguments.len() + 1),
+    }
+}
+
+/// Finds the option closest to the first word of the message,
+/// if it is close enough to be considered a typo.
+fn suggest(options: &[String], message: &str) -> Option<String> {
+    let word = message.split_whitespace().next()?;
+    options
+        .iter()
+        .map(|option| (option, edit_distance(option, word)))
+        .filter(|(option, distance)| *distance > 0 && *distance <= max_typos(option))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(option, _)| option.clone())
+}
+
+/// Maximum edit distance for a word to be considered a typo of the `target`.
+pub(crate) fn max_typos(target: &str) -> usize {
+    (target.chars().count() / 3).max(1)
+}
+
+/// Levenshtein distance between two strings, counted in chars.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_literal_matching() {
+        let node = command!("!reload"; true, ());
+        assert!(node.parse("!reload").is_ok());
+        assert!(node.parse("!reloadXYZ").is_err());
+        assert!(node.parse("!Reload").is_err());
+
+        let node = CommandBuilder::new()
+            .literal(["!hello"])
+            .ignore_case()
+            .choice(["straße", "world"])
+            .ignore_case()
+            .finalize(true, ());
+        let parsed = node.parse("!HeLLo STRASSE").unwrap();
+        assert_eq!(parsed.arguments.key(), vec!["straße"]);
+        assert!(node.parse("!HELLO WORLDS").is_err());
+
+        assert_eq!(strip_option("σας", "ΣΑΣ x", true), Some(" x"));
+        assert_eq!(strip_option("!hi", "!hi\tthere", false), Some("\tthere"));
+        assert_eq!(strip_option("!hi", "!hill", false), None);
+    }
+
+    #[test]
+    fn test_typed_arguments() {
+        let node = CommandBuilder::new()
+            .literal(["!timeout"])
+            .word()
+            .duration()
+            .finalize(true, ());
+        let parsed = node.parse("!timeout user 1h30m").unwrap();
+        assert_eq!(parsed.arguments.key(), vec!["user", "5400s"]);
+
+        assert!(node.parse("!timeout user soon").is_err());
+
+        let err = command!(duration; true, ()).parse("soon").unwrap_err();
+        assert!(err.msg.starts_with("Argument #1 is invalid"));
+
+        let node = command!("!roll"; int(1, 20); true, ());
+        assert!(node.parse("!roll 20").is_ok());
+        assert!(node.parse("!roll 21").is_err());
+    }
+
+    #[test]
+    fn test_named_arguments() {
+        let node = CommandBuilder::new()
+            .literal(["!give"])
+            .word()
+            .named("user")
+            .integer(Some(1), None)
+            .named("amount")
+            .finalize(true, ());
+        let parsed = node.parse("!give bob 10").unwrap();
+        assert_eq!(
+            parsed.arguments.get::<String>("user").as_deref(),
+            Some("bob")
+        );
+        assert_eq!(parsed.arguments.get::<i64>("amount"), Some(10));
+        assert_eq!(parsed.arguments.get::<f64>("amount"), Some(10.0));
+        assert_eq!(parsed.arguments.get::<String>("amount"), None);
+        assert_eq!(parsed.arguments.get_index::<i64>(1), Some(10));
+
+        let node = command!("!give"; word as "user"; int as "amount"; true, ());
+        let parsed = node.parse("!give bob 10").unwrap();
+        assert_eq!(parsed.arguments.get::<i64>("amount"), Some(10));
+    }
+
+    #[test]
+    fn test_optional_arguments() {
+        let node = CommandBuilder::new()
+            .literal(["!hello"])
+            .optional(ArgumentType::Word)
+            .named("name")
+            .finalize(true, ());
+        let parsed = node.parse("!hello bob").unwrap();
+        assert_eq!(
+            parsed.arguments.get::<Option<String>>("name"),
+            Some(Some("bob".to_string()))
+        );
+        let parsed = node.parse("!hello").unwrap();
+        assert_eq!(parsed.arguments.get::<Option<String>>("name"), Some(None));
+
+        let node = command!("!roll"; [int as "sides"] = 6; [word]; true, ());
+        let parsed = node.parse("!roll").unwrap();
+        assert_eq!(parsed.arguments.get::<i64>("sides"), Some(6));
+        assert_eq!(parsed.arguments.len(), 2);
+        let parsed = node.parse("!roll 20 dice").unwrap();
+        assert_eq!(parsed.arguments.get::<i64>("sides"), Some(20));
+        assert_eq!(
+            parsed.arguments.get_index::<String>(1).as_deref(),
+            Some("dice")
+        );
+        // Falls back to the default and parses `dice` as the word
+        let parsed = node.parse("!roll dice").unwrap();
+        assert_eq!(parsed.arguments.get::<i64>("sides"), Some(6));
+        assert!(node.parse("!roll 20 dice more").is_err());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(super::edit_distance("create", "creat"), 1);
+        assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+        assert_eq!(super::edit_distance("", "abc"), 3);
+        assert_eq!(super::edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_best_error() {
+        let node = CommandBuilder::new().literal(["!backup"]).split([
+            command!("create" | "load"; true, ()),
+            command!("delete"; int as "id"; true, ()),
+        ]);
+
+        let err = node.parse("!backup creat").unwrap_err();
+        assert_eq!(err.offset, 8);
+        assert_eq!(err.suggestion.as_deref(), Some("create"));
+
+        let err = node.parse("!backup delete abc").unwrap_err();
+        assert_eq!(err.offset, 15);
+        assert!(err.msg.contains("\"id\" is invalid"));
+
+        let err = node.parse("!backup load now").unwrap_err();
+        assert_eq!(err.offset, 13);
+        assert_eq!(err.suggestion, None);
+
+        let err = node.parse("!bakcup").unwrap_err();
+        assert_eq!(err.offset, 0);
+        assert_eq!(err.suggestion.as_deref(), Some("!backup"));
+        let err = node.parse("!hello").unwrap_err();
+        assert_eq!(err.suggestion, None);
+
+        // On the same offset, the closer suggestion wins
+        let node = CommandBuilder::new()
+            .literal(["!backup"])
+            .split([command!("restore"; true, ()), command!("remove"; true, ())]);
+        let err = node.parse("!backup remore").unwrap_err();
+        assert_eq!(err.suggestion.as_deref(), Some("remove"));
+        let typo = node.parse("!bakcup").unwrap_err();
+        let unknown = command!("!hello"; true, ()).parse("!bakcup").unwrap_err();
+        assert!(typo.is_better_than(&unknown, "!bakcup"));
+        assert!(!unknown.is_better_than(&typo, "!bakcup"));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let source = r#"
+            type = "literal"
+            literals = ["!roll"]
+
+            [[child_nodes]]
+            type = "optional"
+            name = "sides"
+            argument_type = { integer = { min = 1 } }
+            default = 6
+
+            [[child_nodes.child_nodes]]
+            type = "final"
+            value = "roll"
+        "#;
+        let node: CommandNode<String> = toml::from_str(source).unwrap();
+        let parsed = node.parse("!roll").unwrap();
+        assert_eq!(parsed.value, "roll");
+        assert_eq!(parsed.arguments.get::<i64>("sides"), Some(6));
+        assert!(node.parse("!roll 0").is_err());
+
+        let node = command!(
+            "!timeout";
+            word as "user";
+            [duration as "time"] = std::time::Duration::from_secs(600);
+            "yes" | "no";
+            false, "timeout".to_string()
+        );
+        let source = toml::to_string(&node).unwrap();
+        let node: CommandNode<String> = toml::from_str(&source).unwrap();
+        let parsed = node.parse("!timeout bob yes").unwrap();
+        assert_eq!(
+            parsed.arguments.get::<std::time::Duration>("time"),
+            Some(std::time::Duration::from_secs(600))
+        );
+
+        let source = r#"
+            type = "literal"
+            literals = ["!shoutout"]
+            child_nodes = [{ type = "redirect", target = "target" }]
+        "#;
+        let node: CommandNode<String> = toml::from_str(source).unwrap();
+        assert!(matches!(
+            &node.children()[0],
+            CommandNode::Redirect { target, subtree: None } if target == "target"
+        ));
     }
 }
@@ -0,0 +1,106 @@
+use crate::{ArgumentType, CommandNode};
+
+impl ArgumentType {
+    /// Short lowercase name to show in the usage string when the argument has no name.
+    pub fn usage_name(&self) -> &'static str {
+        match self {
+            ArgumentType::Word => "word",
+            ArgumentType::Line => "line",
+            ArgumentType::Tail => "text",
+            ArgumentType::Integer { .. } => "integer",
+            ArgumentType::Float => "number",
+            ArgumentType::Duration => "duration",
+            ArgumentType::Quoted => "text",
+            ArgumentType::Regex(_) => "value",
+            ArgumentType::User => "user",
+        }
+    }
+}
+
+impl<T: Clone> CommandNode<T> {
+    /// Renders a usage string for every branch of the tree, e.g.
+    /// `!backup <create|load>` or `!hello [name]`.
+    /// Required arguments are shown in `<>`, optional ones in `[]`,
+    /// and alternatives are separated by `|`.
+    pub fn usage(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.usage_impl(Vec::new(), &mut lines);
+        lines
+    }
+
+    fn usage_impl(&self, mut prefix: Vec<String>, lines: &mut Vec<String>) {
+        if let CommandNode::Redirect { target, subtree } = self {
+            match subtree {
+                Some(subtree) => {
+                    for child in subtree.iter() {
+                        child.usage_impl(prefix.clone(), lines);
+                    }
+                }
+                None => {
+                    prefix.push(format!("<{target}...>"));
+                    lines.push(prefix.join(" "));
+                }
+            }
+            return;
+        }
+
+        let Some(part) = self.usage_part() else {
+            lines.push(prefix.join(" "));
+            return;
+        };
+        prefix.push(part);
+        for child in self.children() {
+            child.usage_impl(prefix.clone(), lines);
+        }
+    }
+
+    /// Part of the usage string that describes this node,
+    /// or `None` for a final node and a redirect.
+    pub(crate) fn usage_part(&self) -> Option<String> {
+        let part = match self {
+            CommandNode::Literal { literals, .. } => literals.join("|"),
+            CommandNode::Argument {
+                name,
+                argument_type,
+                ..
+            } => {
+                let name = name.as_deref().unwrap_or(argument_type.usage_name());
+                format!("<{name}>")
+            }
+            CommandNode::ArgumentChoice { choices, .. } => format!("<{}>", choices.join("|")),
+            CommandNode::Optional {
+                name,
+                argument_type,
+                ..
+            } => {
+                let name = name.as_deref().unwrap_or(argument_type.usage_name());
+                format!("[{name}]")
+            }
+            CommandNode::Redirect { .. } | CommandNode::Final { .. } => return None,
+        };
+        Some(part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_usage() {
+        let node = command!("!backup"; "create" | "load"; true, ());
+        assert_eq!(node.usage(), vec!["!backup <create|load>"]);
+
+        let node = command!("!hello"; word; true, ());
+        assert_eq!(node.usage(), vec!["!hello <word>"]);
+
+        let node = CommandBuilder::new().literal(["!quote", "!q"]).split([
+            command!([int as "id"]; true, ()),
+            command!("add"; quoted as "text"; word as "author"; true, ()),
+        ]);
+        assert_eq!(
+            node.usage(),
+            vec!["!quote|!q [id]", "!quote|!q add <text> <author>"]
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use crate::{strip_option, ArgumentType, CommandNode};
+
+/// A potential problem in a command tree, found by [CommandNode::validate].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Usage-like path to the node with the problem, e.g. `!backup <create|load>`.
+    pub path: String,
+    pub kind: WarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WarningKind {
+    /// The same literal or choice is listed more than once.
+    Duplicate { option: String },
+    /// A literal or choice starts with an earlier one, which is tried first.
+    /// Within a single node, the later option is never matched.
+    Shadowed { option: String, shadowed_by: String },
+    /// A child node can never be reached.
+    Unreachable { node: String, reason: String },
+    /// A branch does not end with a final node, so it never activates.
+    NoFinal,
+    /// The same literal starts two different trees.
+    Conflict { literal: String, other: String },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: ", self.path)?;
+        match &self.kind {
+            WarningKind::Duplicate { option } => write!(f, "{option:?} is listed more than once"),
+            WarningKind::Shadowed {
+                option,
+                shadowed_by,
+            } => write!(
+                f,
+                "{option:?} starts with {shadowed_by:?}, which is tried first"
+            ),
+            WarningKind::Unreachable { node, reason } => {
+                write!(f, "{node} is unreachable, because {reason}")
+            }
+            WarningKind::NoFinal => write!(f, "the branch does not end with a final node"),
+            WarningKind::Conflict { literal, other } => {
+                write!(f, "{literal:?} also starts the command {other:?}")
+            }
+        }
+    }
+}
+
+impl<T: Clone> CommandNode<T> {
+    /// Checks the tree for ambiguous literals and choices, and for branches that can never activate.
+    pub fn validate(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        self.validate_impl("", &mut warnings);
+        warnings
+    }
+
+    fn validate_impl(&self, path: &str, warnings: &mut Vec<Warning>) {
+        let path = match (self.usage_part(), self) {
+            (Some(part), _) if path.is_empty() => part,
+            (Some(part), _) => format!("{path} {part}"),
+            // The linked subtree continues the branch, and is checked like the children
+            (
+                None,
+                CommandNode::Redirect {
+                    subtree: Some(_), ..
+                },
+            ) => path.to_owned(),
+            (None, _) => return,
+        };
+        let warn = |warnings: &mut Vec<Warning>, kind| {
+            warnings.push(Warning {
+                path: path.clone(),
+                kind,
+            })
+        };
+
+        if let Some((options, ignore_case)) = self.options() {
+            for kind in check_options(options, ignore_case) {
+                warn(warnings, kind);
+            }
+        }
+
+        let child_nodes = self.children();
+        if child_nodes.is_empty() {
+            warn(warnings, WarningKind::NoFinal);
+            return;
+        }
+
+        // Nothing is left to parse after a greedy argument
+        if let CommandNode::Argument {
+            argument_type: ArgumentType::Tail,
+            ..
+        } = self
+        {
+            for child_node in child_nodes.iter().filter(|node| !node.accepts_empty()) {
+                let node = child_node.describe();
+                let reason = "the text argument before it consumes the whole message".to_owned();
+                warn(warnings, WarningKind::Unreachable { node, reason });
+            }
+        }
+
+        // Siblings are tried in order, and the first successful one is used
+        for (i, child_node) in child_nodes.iter().enumerate() {
+            let shadowed_by = child_nodes[..i].iter().find(|earlier| {
+                earlier.accepts_any() && (earlier.accepts_empty() || !child_node.accepts_empty())
+                    || earlier.accepts_empty()
+                        && matches!(
+                            child_node,
+                            CommandNode::Final {
+                                expects_empty_message: true,
+                                ..
+                            }
+                        )
+            });
+            if let Some(earlier) = shadowed_by {
+                let node = child_node.describe();
+                let reason = format!("{} before it always matches", earlier.describe());
+                warn(warnings, WarningKind::Unreachable { node, reason });
+            }
+        }
+
+        // Literals and choices of sibling nodes.
+        // Options within a single node are checked separately
+        let sibling_options: Vec<(usize, &String, bool)> = child_nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| {
+                let (options, ignore_case) = node.options()?;
+                Some(options.iter().map(move |option| (i, option, ignore_case)))
+            })
+            .flatten()
+            .collect();
+        for (i, &(node, option, _)) in sibling_options.iter().enumerate() {
+            if let Some(kind) = sibling_options[..i]
+                .iter()
+                .filter(|(other, ..)| *other != node)
+                .find_map(|(_, earlier, ignore_case)| option_warning(option, earlier, *ignore_case))
+            {
+                warn(warnings, kind);
+            }
+        }
+
+        for child_node in child_nodes {
+            child_node.validate_impl(&path, warnings);
+        }
+    }
+
+    /// Literals or choices of the node, and whether they ignore case.
+    fn options(&self) -> Option<(&[String], bool)> {
+        match self {
+            CommandNode::Literal {
+                literals,
+                ignore_case,
+                ..
+            } => Some((literals, *ignore_case)),
+            CommandNode::ArgumentChoice {
+                choices,
+                ignore_case,
+                ..
+            } => Some((choices, *ignore_case)),
+            _ => None,
+        }
+    }
+
+    /// Short description of the node for the warnings.
+    fn describe(&self) -> String {
+        match self {
+            CommandNode::Literal { literals, .. } => format!("literal {:?}", literals.join("|")),
+            CommandNode::Argument { argument_type, .. } => format!("{argument_type} argument"),
+            CommandNode::ArgumentChoice { choices, .. } => {
+                format!("choice {:?}", choices.join("|"))
+            }
+            CommandNode::Optional { argument_type, .. } => {
+                format!("optional {argument_type} argument")
+            }
+            CommandNode::Redirect { target, .. } => format!("redirect to {target:?}"),
+            CommandNode::Final { .. } => "final node".to_owned(),
+        }
+    }
+
+    /// Whether the branch activates when there is nothing left in the message.
+    fn accepts_empty(&self) -> bool {
+        match self {
+            CommandNode::Final { .. } => true,
+            CommandNode::Optional { child_nodes, .. } => {
+                child_nodes.iter().any(CommandNode::accepts_empty)
+            }
+            CommandNode::Redirect { .. } => self.children().iter().any(CommandNode::accepts_empty),
+            _ => false,
+        }
+    }
+
+    /// Whether the branch activates on any non-empty rest of the message.
+    fn accepts_any(&self) -> bool {
+        match self {
+            CommandNode::Final {
+                expects_empty_message,
+                ..
+            } => !expects_empty_message,
+            CommandNode::Argument {
+                argument_type: ArgumentType::Tail,
+                child_nodes,
+                ..
+            }
+            | CommandNode::Optional {
+                argument_type: ArgumentType::Tail,
+                child_nodes,
+                ..
+            } => child_nodes.iter().any(CommandNode::accepts_empty),
+            CommandNode::Optional { child_nodes, .. } => {
+                child_nodes.iter().any(CommandNode::accepts_any)
+            }
+            CommandNode::Redirect { .. } => self.children().iter().any(CommandNode::accepts_any),
+            _ => false,
+        }
+    }
+}
+
+/// Finds duplicate and shadowed options within a single node.
+fn check_options(options: &[String], ignore_case: bool) -> Vec<WarningKind> {
+    options
+        .iter()
+        .enumerate()
+        .filter_map(|(i, option)| {
+            options[..i]
+                .iter()
+                .find_map(|earlier| option_warning(option, earlier, ignore_case))
+        })
+        .collect()
+}
+
+/// Checks whether a message consisting of the `option` would be matched by the `earlier` option.
+fn option_warning(option: &str, earlier: &str, ignore_case: bool) -> Option<WarningKind> {
+    let rest = strip_option(earlier, option, ignore_case)?;
+    Some(if rest.is_empty() {
+        WarningKind::Duplicate {
+            option: option.to_owned(),
+        }
+    } else {
+        WarningKind::Shadowed {
+            option: option.to_owned(),
+            shadowed_by: earlier.to_owned(),
+        }
+    })
+}
+
+/// Validates every tree and checks for literals that start more than one tree.
+/// The trees are assumed to be tried independently, so a literal that is matched
+/// by another tree's literal (e.g. `!hi` and `!hi there`) is reported, as both trees may activate.
+pub fn validate_trees<'a, T: Clone + 'a>(
+    trees: impl IntoIterator<Item = &'a CommandNode<T>>,
+) -> Vec<Warning> {
+    let trees: Vec<_> = trees.into_iter().collect();
+    let mut warnings: Vec<Warning> = trees.iter().flat_map(|tree| tree.validate()).collect();
+
+    for (i, tree) in trees.iter().enumerate() {
+        let Some(path) = tree.usage_part() else {
+            continue;
+        };
+        let Some((literals, ignore_case)) = tree.options() else {
+            continue;
+        };
+        for literal in literals {
+            for other in &trees[..i] {
+                let Some((other_literals, other_ignore_case)) = other.options() else {
+                    continue;
+                };
+                let Some(other_literal) = other_literals.iter().find(|other| {
+                    strip_option(other, literal, other_ignore_case).is_some()
+                        || strip_option(literal, other, ignore_case).is_some()
+                }) else {
+                    continue;
+                };
+                warnings.push(Warning {
+                    path: path.clone(),
+                    kind: WarningKind::Conflict {
+                        literal: literal.clone(),
+                        other: other_literal.clone(),
+                    },
+                });
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn kinds(warnings: Vec<Warning>) -> Vec<WarningKind> {
+        warnings.into_iter().map(|warning| warning.kind).collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let node = command!("!hi", "!hill", "!hi there", "!hi"; true, ());
+        assert_eq!(
+            kinds(node.validate()),
+            vec![
+                WarningKind::Shadowed {
+                    option: "!hi there".to_owned(),
+                    shadowed_by: "!hi".to_owned()
+                },
+                WarningKind::Duplicate {
+                    option: "!hi".to_owned()
+                },
+            ]
+        );
+
+        let node = CommandBuilder::new()
+            .literal(["!hello", "!HELLO"])
+            .ignore_case()
+            .finalize(true, ());
+        assert_eq!(node.validate().len(), 1);
+
+        let node = CommandBuilder::new()
+            .literal(["!say"])
+            .argument(ArgumentType::Tail)
+            .split([command!("now"; true, ()), command!(true, ())]);
+        let warnings = node.validate();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "!say <text>");
+        assert!(matches!(warnings[0].kind, WarningKind::Unreachable { .. }));
+
+        let node = CommandBuilder::new()
+            .literal(["!backup"])
+            .split([command!(false, ()), command!("create" | "load"; true, ())]);
+        assert_eq!(node.validate().len(), 1);
+
+        let node = CommandBuilder::new().literal(["!backup"]).split([
+            command!("create" | "load"; true, ()),
+            command!("delete"; int; true, ()),
+            command!(true, ()),
+        ]);
+        assert!(node.validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_redirect() {
+        let mut subtrees = Subtrees::new();
+        subtrees.insert(
+            "action",
+            vec![
+                command!("create" | "load" | "create"; true, ()),
+                command!(false, ()),
+                command!("delete"; true, ()),
+            ],
+        );
+        let subtrees = subtrees.link().unwrap();
+        let mut node = CommandBuilder::new()
+            .literal(["!backup"])
+            .redirect("action");
+        node.link(&subtrees).unwrap();
+
+        let warnings = node.validate();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].path, "!backup");
+        assert!(matches!(warnings[0].kind, WarningKind::Unreachable { .. }));
+        assert_eq!(
+            warnings[1].kind,
+            WarningKind::Duplicate {
+                option: "create".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_trees() {
+        let trees = [
+            command!("!hello"; true, ()),
+            command!("!hi"; true, ()),
+            command!("!hill"; true, ()),
+            command!("!hi there"; true, ()),
+        ];
+        assert_eq!(
+            kinds(validate_trees(&trees)),
+            vec![WarningKind::Conflict {
+                literal: "!hi there".to_owned(),
+                other: "!hi".to_owned()
+            }]
+        );
+    }
+}
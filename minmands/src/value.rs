@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A parsed argument value.
+///
+/// Serialized as a plain value: a number, a string or a duration string like `"10m"`.
+/// Durations are read back as strings, which [FromArgument] converts to [Duration],
+/// so that a string like `"10m"` stays a string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    #[serde(serialize_with = "serialize_duration", skip_deserializing)]
+    Duration(Duration),
+    /// An optional argument that was omitted and had no default value.
+    #[serde(skip)]
+    Empty,
+}
+
+impl std::fmt::Display for ArgumentValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgumentValue::String(value) => write!(f, "{value}"),
+            ArgumentValue::Integer(value) => write!(f, "{value}"),
+            ArgumentValue::Float(value) => write!(f, "{value}"),
+            ArgumentValue::Duration(value) => write!(f, "{value:?}"),
+            ArgumentValue::Empty => Ok(()),
+        }
+    }
+}
+
+impl From<String> for ArgumentValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for ArgumentValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<i64> for ArgumentValue {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+
+impl From<f64> for ArgumentValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<Duration> for ArgumentValue {
+    fn from(value: Duration) -> Self {
+        Self::Duration(value)
+    }
+}
+
+/// A single parsed argument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argument {
+    /// Name of the argument, if it was given one in the tree.
+    pub name: Option<String>,
+    pub value: ArgumentValue,
+}
+
+/// List of arguments in the order they were parsed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Arguments {
+    arguments: Vec<Argument>,
+}
+
+impl Arguments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, name: Option<String>, value: ArgumentValue) {
+        self.arguments.push(Argument { name, value });
+    }
+
+    pub fn len(&self) -> usize {
+        self.arguments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arguments.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Argument> {
+        self.arguments.iter()
+    }
+
+    /// Get the first argument with the given name converted to `T`.
+    /// Returns `None` if there is no such argument or it has a different type.
+    pub fn get<T: FromArgument>(&self, name: &str) -> Option<T> {
+        self.arguments
+            .iter()
+            .find(|argument| argument.name.as_deref() == Some(name))
+            .and_then(|argument| T::from_argument(&argument.value))
+    }
+
+    /// Get the argument at the given position converted to `T`.
+    /// Returns `None` if there is no such argument or it has a different type.
+    pub fn get_index<T: FromArgument>(&self, index: usize) -> Option<T> {
+        self.arguments
+            .get(index)
+            .and_then(|argument| T::from_argument(&argument.value))
+    }
+
+    /// Textual representation of all arguments. Different inputs that parse
+    /// into the same values (e.g. `90m` and `1h30m`) produce the same key.
+    pub fn key(&self) -> Vec<String> {
+        self.arguments
+            .iter()
+            .map(|argument| argument.value.to_string())
+            .collect()
+    }
+}
+
+impl IntoIterator for Arguments {
+    type Item = Argument;
+    type IntoIter = std::vec::IntoIter<Argument>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.arguments.into_iter()
+    }
+}
+
+/// Conversion from a parsed [ArgumentValue].
+pub trait FromArgument: Sized {
+    fn from_argument(value: &ArgumentValue) -> Option<Self>;
+}
+
+impl FromArgument for ArgumentValue {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromArgument for String {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        match value {
+            ArgumentValue::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromArgument for i64 {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        match value {
+            ArgumentValue::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl FromArgument for f64 {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        match value {
+            ArgumentValue::Integer(value) => Some(*value as f64),
+            ArgumentValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Also converts duration strings, see [ArgumentValue].
+impl FromArgument for Duration {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        match value {
+            ArgumentValue::Duration(value) => Some(*value),
+            ArgumentValue::String(value) => crate::parse_duration(value),
+            _ => None,
+        }
+    }
+}
+
+/// Converts [ArgumentValue::Empty] into `None`.
+impl<T: FromArgument> FromArgument for Option<T> {
+    fn from_argument(value: &ArgumentValue) -> Option<Self> {
+        match value {
+            ArgumentValue::Empty => Some(None),
+            _ => T::from_argument(value).map(Some),
+        }
+    }
+}
+
+/// Serializes a [Duration] as a string in seconds, e.g. `"0.0005s"`,
+/// which [crate::parse_duration] reads back.
+fn serialize_duration<S: serde::Serializer>(
+    value: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{}s", value.as_secs_f64()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Config {
+            value: ArgumentValue,
+        }
+        let round_trip = |value: ArgumentValue| {
+            let source = toml::to_string(&Config { value }).unwrap();
+            toml::from_str::<Config>(&source).unwrap().value
+        };
+
+        for value in ["10m", "90", "hello"] {
+            let value = ArgumentValue::String(value.to_owned());
+            assert_eq!(round_trip(value.clone()), value);
+        }
+        assert_eq!(
+            round_trip(ArgumentValue::Integer(90)),
+            ArgumentValue::Integer(90)
+        );
+        assert_eq!(
+            round_trip(ArgumentValue::Float(1.5)),
+            ArgumentValue::Float(1.5)
+        );
+
+        for duration in [
+            Duration::from_secs(5400),
+            Duration::from_micros(500),
+            Duration::from_nanos(20),
+            Duration::from_nanos(1_100_000_001),
+        ] {
+            let value = round_trip(ArgumentValue::Duration(duration));
+            assert_eq!(Duration::from_argument(&value), Some(duration));
+        }
+        assert_eq!(
+            String::from_argument(&ArgumentValue::String("10m".to_owned())).as_deref(),
+            Some("10m")
+        );
+    }
+}
@@ -9,7 +9,7 @@ use crossterm::terminal::{disable_raw_mode, EnterAlternateScreen, LeaveAlternate
 use tui::backend::CrosstermBackend;
 
 use crate::client::TwitchClient;
-use crate::config::Config;
+use crate::config::{CommandEdit, Config, DataFormat};
 use crate::model::Model;
 
 const TARGET_DELTA_TIME: f64 = 1.0 / 20.0;
@@ -33,7 +33,19 @@ pub enum AppAction {
     /// Reload the configuration file.
     ReloadConfig,
     /// Send message to twitch chat.
-    Say { message: String },
+    Say {
+        message: String,
+    },
+    /// Change the simple commands, save them and report the result in chat.
+    EditCommands(CommandEdit),
+    /// Save the current values of the counters.
+    SaveCounters,
+    SaveQuotes,
+    SaveQueue,
+    /// Write all quotes to a file in the config folder and report it in chat.
+    ExportQuotes(DataFormat),
+    /// Add the quotes from a file in the config folder, save them and report it in chat.
+    ImportQuotes(DataFormat),
 }
 
 impl App {
@@ -42,10 +54,13 @@ impl App {
         config: Config,
         channel_login: String,
     ) -> color_eyre::Result<Self> {
+        let counters = config.load_counters()?;
+        let quotes = config.load_quotes()?;
+        let queue = config.load_queue()?;
         Ok(Self {
             client,
             terminal: Self::init_terminal().wrap_err("when setting up a terminal")?,
-            model: Model::new(&config),
+            model: Model::new(&config, channel_login.clone(), counters, quotes, queue),
             render: Render::new(),
             channel_login,
             config,
@@ -166,17 +181,80 @@ impl App {
                 self.model.reload(&config);
                 self.config = config;
             }
-            AppAction::Say { message } => {
-                self.client
-                    .irc
-                    .say(self.channel_login.clone(), message)
-                    .await
-                    .wrap_err("when sending a message to twitch")?;
+            AppAction::Say { message } => self.say(message).await?,
+            AppAction::EditCommands(edit) => {
+                let message = match self.config.commands.apply(&edit) {
+                    Ok(()) => {
+                        self.model.reload(&self.config);
+                        match self.config.save_commands() {
+                            Ok(()) => edit.summary(),
+                            Err(err) => {
+                                log::error!("{:?}", err);
+                                format!(
+                                    "{}, but could not save it, so it is lost on restart",
+                                    edit.summary()
+                                )
+                            }
+                        }
+                    }
+                    Err(err) => err.to_string(),
+                };
+                self.say(message).await?;
+            }
+            AppAction::SaveCounters => {
+                if let Err(err) = self.config.save_counters(&self.model.counters) {
+                    log::error!("{:?}", err);
+                }
+            }
+            AppAction::SaveQuotes => {
+                if let Err(err) = self.config.save_quotes(&self.model.quotes) {
+                    log::error!("{:?}", err);
+                }
+            }
+            AppAction::SaveQueue => {
+                if let Err(err) = self.config.save_queue(&self.model.queue) {
+                    log::error!("{:?}", err);
+                }
+            }
+            AppAction::ExportQuotes(format) => {
+                let message = match self.config.export_quotes(&self.model.quotes, format) {
+                    Ok(file) => format!("Exported {} quotes to {}", self.model.quotes.len(), file),
+                    Err(err) => {
+                        log::error!("{:?}", err);
+                        "Failed to export quotes".to_owned()
+                    }
+                };
+                self.say(message).await?;
+            }
+            AppAction::ImportQuotes(format) => {
+                let message = match self.config.import_quotes(format) {
+                    Ok(quotes) => {
+                        let count = self.model.quotes.import(quotes);
+                        if let Err(err) = self.config.save_quotes(&self.model.quotes) {
+                            log::error!("{:?}", err);
+                        }
+                        format!("Imported {count} quotes")
+                    }
+                    Err(err) => {
+                        log::error!("{:?}", err);
+                        "Failed to import quotes".to_owned()
+                    }
+                };
+                self.say(message).await?;
             }
         }
         Ok(())
     }
 
+    /// Send message to twitch chat.
+    async fn say(&self, message: String) -> color_eyre::Result<()> {
+        self.client
+            .irc
+            .say(self.channel_login.clone(), message)
+            .await
+            .wrap_err("when sending a message to twitch")
+    }
+
     /// Update the app over time.
     async fn update(&mut self, delta_time: f64) -> color_eyre::Result<()> {
         let actions = self
@@ -1,10 +1,13 @@
 mod chat;
+mod poll;
+mod queue;
+mod raffle;
 
 use super::{Backend, Terminal};
 
 use color_eyre::eyre::Context;
 use tui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::Span,
     widgets::{Block, Borders},
@@ -52,13 +55,57 @@ impl Render {
             .constraints([Constraint::Length(30), Constraint::Min(10)].as_ref())
             .split(frame.size());
 
+        let chat_area = self.draw_panels(model, frame, chunks[0]);
         let chat = self.render_chat(&model.chat);
-        frame.render_widget(chat, chunks[0]);
+        frame.render_widget(chat, chat_area);
 
         let logs = self.render_logs();
         frame.render_widget(logs, chunks[1]);
     }
 
+    /// Draw the panels of the running activities, like polls or the viewer queue, on the right side of the area.
+    /// Returns the rest of the area.
+    fn draw_panels(&self, model: &Model, frame: &mut Frame, area: Rect) -> Rect {
+        enum Panel<'a> {
+            Poll(&'a Poll),
+            Raffle(&'a Raffle),
+            Queue(&'a ViewerQueue),
+        }
+
+        let panels: Vec<Panel> = [
+            model.poll.as_ref().map(Panel::Poll),
+            model.raffle.as_ref().map(Panel::Raffle),
+            (model.queue.open || !model.queue.is_empty()).then_some(Panel::Queue(&model.queue)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if panels.is_empty() {
+            return area;
+        }
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Length(40)].as_ref())
+            .split(area);
+        let constraints: Vec<Constraint> = panels
+            .iter()
+            .map(|_| Constraint::Ratio(1, panels.len() as u32))
+            .collect();
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(columns[1]);
+        for (panel, &area) in panels.into_iter().zip(rows.iter()) {
+            match panel {
+                Panel::Poll(poll) => frame.render_widget(self.render_poll(poll), area),
+                Panel::Raffle(raffle) => frame.render_widget(self.render_raffle(raffle), area),
+                Panel::Queue(queue) => frame.render_widget(self.render_queue(queue), area),
+            }
+        }
+        columns[0]
+    }
+
     fn render_logs(&self) -> TuiLoggerWidget {
         TuiLoggerWidget::default()
             .style_error(Style::default().fg(Color::Red))
@@ -0,0 +1,41 @@
+use tui::{
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::model::Poll;
+
+use super::Render;
+
+/// Width of the bar of an option that got all votes.
+const BAR_WIDTH: usize = 20;
+
+impl Render {
+    pub fn render_poll<'a>(&self, poll: &'a Poll) -> impl Widget + 'a {
+        let total = poll.total_votes();
+        let mut lines = vec![Spans::from(Span::styled(
+            &poll.question,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))];
+        for (index, (option, votes)) in poll.results().into_iter().enumerate() {
+            let percent = votes * 100 / total.max(1);
+            lines.push(Spans::from(format!("{}) {}", index + 1, option)));
+            lines.push(Spans::from(format!(
+                "{} {} ({}%)",
+                "█".repeat(votes * BAR_WIDTH / total.max(1)),
+                votes,
+                percent
+            )));
+        }
+        lines.push(Spans::from(format!(
+            "{} votes, {}s left",
+            total,
+            poll.time_left.max(0.0).ceil()
+        )));
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Poll").borders(Borders::all()))
+    }
+}
@@ -0,0 +1,35 @@
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::model::ViewerQueue;
+
+use super::Render;
+
+impl Render {
+    pub fn render_queue<'a>(&self, queue: &'a ViewerQueue) -> impl Widget + 'a {
+        let state = if queue.open { "open" } else { "closed" };
+        let mut lines = vec![Spans::from(Span::styled(
+            format!("{}, {} waiting", state, queue.len()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))];
+        for (index, entry) in queue.iter().enumerate() {
+            // Subscribers that skipped ahead
+            let style = if entry.priority {
+                Style::default().fg(Color::Magenta)
+            } else {
+                Style::default()
+            };
+            lines.push(Spans::from(Span::styled(
+                format!("{}. {}", index + 1, entry.name),
+                style,
+            )));
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Queue").borders(Borders::all()))
+    }
+}
@@ -0,0 +1,42 @@
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::model::Raffle;
+
+use super::Render;
+
+impl Render {
+    pub fn render_raffle<'a>(&self, raffle: &'a Raffle) -> impl Widget + 'a {
+        let state = if raffle.open { "open" } else { "closed" };
+        let mut lines = vec![Spans::from(vec![
+            Span::styled(
+                &raffle.keyword,
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" ({}, {} entrants)", state, raffle.entrants.len())),
+        ])];
+        for entrant in &raffle.entrants {
+            let style = if raffle.has_won(&entrant.id) {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            let weight = if entrant.weight == 1 {
+                String::new()
+            } else {
+                format!(" x{}", entrant.weight)
+            };
+            lines.push(Spans::from(Span::styled(
+                format!("{}{}", entrant.name, weight),
+                style,
+            )));
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title("Raffle").borders(Borders::all()))
+    }
+}
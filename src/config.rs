@@ -1,31 +1,350 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::BTreeMap, path::PathBuf};
 
 use color_eyre::eyre::Context;
-use serde::Deserialize;
+use indexmap::IndexMap;
+use minmands::CommandNode;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{AuthorityLevel, CooldownScope, Counters, Quotes, ViewerQueue};
 
 #[derive(Default)]
 pub struct Config {
     /// Path to the config directory.
     pub path: PathBuf,
     pub commands: SimpleCommands,
+    pub timers: Vec<TimerConfig>,
+    pub raffle: RaffleConfig,
+    pub queue: QueueConfig,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 pub struct SimpleCommands {
+    /// Default cooldown in seconds.
     #[serde(default)]
     pub cooldown: f64,
-    pub commands: HashMap<String, String>,
+    /// Default cooldown scope.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub cooldown_scope: CooldownScope,
+    /// Default authority level that is not affected by cooldowns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown_bypass: Option<AuthorityLevel>,
+    /// Whether to tell callers in chat why a command did not fire by default.
+    /// The replies go to chat, as Twitch does not deliver whispers sent over IRC.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub feedback: bool,
+    /// Minimal time in seconds between feedback messages of a single command,
+    /// and between suggestions for mistyped commands.
+    #[serde(
+        default = "default_feedback_cooldown",
+        skip_serializing_if = "is_default_feedback_cooldown"
+    )]
+    pub feedback_cooldown: f64,
+    /// Kept in the order of the file, so that saving it does not shuffle the commands.
+    pub commands: IndexMap<String, SimpleCommand>,
+    /// Commands with arguments, described as full command trees.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trees: Vec<CommandTreeConfig>,
+    /// Named subtrees that nodes in [SimpleCommands::trees] can redirect to.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub subtrees: IndexMap<String, Vec<CommandNode<String>>>,
+}
+
+/// Settings of a configured command.
+/// Unset values default to the ones in [SimpleCommands].
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct CommandSettings {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub authority: AuthorityLevel,
+    /// Command cooldown in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_scope: Option<CooldownScope>,
+    /// Authority level that is not affected by the cooldown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_bypass: Option<AuthorityLevel>,
+    /// Whether to tell callers in chat why the command did not fire.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<bool>,
+    /// Minimal time in seconds between feedback messages of the command.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_cooldown: Option<f64>,
+}
+
+/// A command that sends a response, with optional settings.
+/// Can be written as just the response: `name = "response"`,
+/// or as a table: `[commands.name] response = "...", aliases = ["alias"]`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "SimpleCommandConfig", into = "SimpleCommandConfig")]
+pub struct SimpleCommand {
+    pub response: String,
+    /// Other names of the command.
+    pub aliases: Vec<String>,
+    pub settings: CommandSettings,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SimpleCommandConfig {
+    Response(String),
+    Table {
+        response: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        aliases: Vec<String>,
+        #[serde(flatten)]
+        settings: CommandSettings,
+    },
+}
+
+impl From<SimpleCommandConfig> for SimpleCommand {
+    fn from(config: SimpleCommandConfig) -> Self {
+        match config {
+            SimpleCommandConfig::Response(response) => Self {
+                response,
+                aliases: Vec::new(),
+                settings: CommandSettings::default(),
+            },
+            SimpleCommandConfig::Table {
+                response,
+                aliases,
+                settings,
+            } => Self {
+                response,
+                aliases,
+                settings,
+            },
+        }
+    }
+}
+
+impl From<SimpleCommand> for SimpleCommandConfig {
+    fn from(command: SimpleCommand) -> Self {
+        if command.aliases.is_empty() && command.settings == CommandSettings::default() {
+            Self::Response(command.response)
+        } else {
+            Self::Table {
+                response: command.response,
+                aliases: command.aliases,
+                settings: command.settings,
+            }
+        }
+    }
+}
+
+/// A configurable command tree, whose final nodes hold the response to send.
+#[derive(Serialize, Deserialize)]
+pub struct CommandTreeConfig {
+    #[serde(flatten)]
+    pub settings: CommandSettings,
+    pub root: CommandNode<String>,
 }
 
 impl Default for SimpleCommands {
     fn default() -> Self {
         Self {
             cooldown: 30.0,
+            cooldown_scope: CooldownScope::default(),
+            cooldown_bypass: None,
+            feedback: false,
+            feedback_cooldown: default_feedback_cooldown(),
             commands: Default::default(),
+            trees: Default::default(),
+            subtrees: Default::default(),
         }
     }
 }
 
+fn default_feedback_cooldown() -> f64 {
+    10.0
+}
+
+fn is_default_feedback_cooldown(value: &f64) -> bool {
+    *value == default_feedback_cooldown()
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+#[derive(Deserialize, Default)]
+struct TimersConfig {
+    #[serde(default)]
+    timers: Vec<TimerConfig>,
+}
+
+/// Shortest time in seconds between timer messages, so that a typo does not flood the chat.
+const MIN_TIMER_INTERVAL: f64 = 60.0;
+
+impl TimersConfig {
+    /// Raises the intervals that are too short to [MIN_TIMER_INTERVAL].
+    fn clamp_intervals(mut self) -> Self {
+        for timer in &mut self.timers {
+            if timer.interval.is_nan() || timer.interval < MIN_TIMER_INTERVAL {
+                log::warn!(
+                    "Timer {:?} has an interval of {} seconds, using the minimum of {} instead",
+                    timer.name,
+                    timer.interval,
+                    MIN_TIMER_INTERVAL
+                );
+                timer.interval = MIN_TIMER_INTERVAL;
+            }
+        }
+        self
+    }
+}
+
+/// A message that is sent to chat periodically.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimerConfig {
+    /// Used to enable or disable the timer from chat.
+    pub name: String,
+    /// Time in seconds between messages.
+    pub interval: f64,
+    /// Chat messages required since the last time, so that an inactive chat is not spammed.
+    #[serde(default)]
+    pub min_lines: usize,
+    /// Sent in turns.
+    pub messages: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RaffleConfig {
+    /// Chance to win of each authority level, relative to others.
+    /// Levels that are not listed have the weight of the level below, or 1.
+    #[serde(default)]
+    pub weights: BTreeMap<AuthorityLevel, u32>,
+}
+
+impl RaffleConfig {
+    pub fn weight(&self, authority: AuthorityLevel) -> u32 {
+        self.weights
+            .range(..=authority)
+            .next_back()
+            .map_or(1, |(_, &weight)| weight)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct QueueConfig {
+    /// Whether subscribers join the viewer queue ahead of other viewers.
+    #[serde(default)]
+    pub subscriber_priority: bool,
+}
+
+/// Format of the files that data is exported to and imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Toml,
+    Json,
+}
+
+impl DataFormat {
+    /// Parses the name of the format, e.g. `json`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A change to the simple commands, requested from chat.
+/// Names are given without the `!` prefix.
+#[derive(Debug, Clone)]
+pub enum CommandEdit {
+    Add { name: String, response: String },
+    Edit { name: String, response: String },
+    Delete { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandEditError {
+    AlreadyExists(String),
+    NotFound(String),
+}
+
+impl std::error::Error for CommandEditError {}
+
+impl std::fmt::Display for CommandEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandEditError::AlreadyExists(name) => write!(f, "Command !{name} already exists"),
+            CommandEditError::NotFound(name) => write!(f, "Unknown command: !{name}"),
+        }
+    }
+}
+
+impl CommandEdit {
+    /// Confirmation message after the edit is applied.
+    pub fn summary(&self) -> String {
+        match self {
+            CommandEdit::Add { name, .. } => format!("Added command !{name}"),
+            CommandEdit::Edit { name, .. } => format!("Updated command !{name}"),
+            CommandEdit::Delete { name } => format!("Deleted command !{name}"),
+        }
+    }
+}
+
+impl SimpleCommands {
+    /// Finds the key of the command with the given name or alias, ignoring case.
+    fn find(&self, name: &str) -> Option<&str> {
+        self.commands
+            .iter()
+            .find(|(key, command)| {
+                std::iter::once(*key)
+                    .chain(&command.aliases)
+                    .any(|alias| alias.eq_ignore_ascii_case(name))
+            })
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Applies the change, keeping the order of the other commands.
+    pub fn apply(&mut self, edit: &CommandEdit) -> Result<(), CommandEditError> {
+        match edit {
+            CommandEdit::Add { name, response } => {
+                if self.find(name).is_some() {
+                    return Err(CommandEditError::AlreadyExists(name.to_owned()));
+                }
+                let command = SimpleCommand {
+                    response: response.to_owned(),
+                    aliases: Vec::new(),
+                    settings: CommandSettings::default(),
+                };
+                self.commands.insert(name.to_lowercase(), command);
+            }
+            CommandEdit::Edit { name, response } => {
+                let key = self
+                    .find(name)
+                    .ok_or_else(|| CommandEditError::NotFound(name.to_owned()))?
+                    .to_owned();
+                self.commands[&key].response = response.to_owned();
+            }
+            CommandEdit::Delete { name } => {
+                let key = self
+                    .find(name)
+                    .ok_or_else(|| CommandEditError::NotFound(name.to_owned()))?
+                    .to_owned();
+                self.commands.shift_remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Config {
     /// Loads the config from the given folder.
     pub fn load(path: impl AsRef<std::path::Path>) -> color_eyre::Result<Self> {
@@ -34,7 +353,82 @@ impl Config {
         let commands =
             read_or_default(path.join("commands.toml")).wrap_err("when loading client secret")?;
 
-        Ok(Self { path, commands })
+        let timers: TimersConfig =
+            read_or_default(path.join("timers.toml")).wrap_err("when loading timers")?;
+
+        let raffle = read_or_default(path.join("raffle.toml")).wrap_err("when loading raffle")?;
+
+        let queue = read_or_default(path.join("queue.toml")).wrap_err("when loading queue")?;
+
+        Ok(Self {
+            path,
+            commands,
+            timers: timers.clamp_intervals().timers,
+            raffle,
+            queue,
+        })
+    }
+
+    /// Loads the counters saved in the config folder.
+    pub fn load_counters(&self) -> color_eyre::Result<Counters> {
+        read_or_default(self.path.join("counters.toml")).wrap_err("when loading counters")
+    }
+
+    pub fn save_counters(&self, counters: &Counters) -> color_eyre::Result<()> {
+        crate::util::fs::write_toml(counters, self.path.join("counters.toml"))
+            .wrap_err("when saving counters")
+    }
+
+    /// Loads the quotes saved in the config folder.
+    pub fn load_quotes(&self) -> color_eyre::Result<Quotes> {
+        read_or_default(self.path.join("quotes.toml")).wrap_err("when loading quotes")
+    }
+
+    pub fn save_quotes(&self, quotes: &Quotes) -> color_eyre::Result<()> {
+        crate::util::fs::write_toml(quotes, self.path.join("quotes.toml"))
+            .wrap_err("when saving quotes")
+    }
+
+    /// Loads the viewer queue saved in the config folder.
+    pub fn load_queue(&self) -> color_eyre::Result<ViewerQueue> {
+        read_or_default(self.path.join("queue_state.toml")).wrap_err("when loading queue")
+    }
+
+    pub fn save_queue(&self, queue: &ViewerQueue) -> color_eyre::Result<()> {
+        crate::util::fs::write_toml(queue, self.path.join("queue_state.toml"))
+            .wrap_err("when saving queue")
+    }
+
+    /// Writes the quotes to `quotes_export.<format>` in the config folder,
+    /// and returns the name of the file.
+    pub fn export_quotes(&self, quotes: &Quotes, format: DataFormat) -> color_eyre::Result<String> {
+        let name = format!("quotes_export.{}", format.extension());
+        let path = self.path.join(&name);
+        match format {
+            DataFormat::Toml => crate::util::fs::write_toml(quotes, path),
+            DataFormat::Json => crate::util::fs::write_json(quotes, path),
+        }
+        .wrap_err("when exporting quotes")?;
+        Ok(name)
+    }
+
+    /// Reads the quotes from `quotes_import.<format>` in the config folder.
+    pub fn import_quotes(&self, format: DataFormat) -> color_eyre::Result<Quotes> {
+        let path = self
+            .path
+            .join(format!("quotes_import.{}", format.extension()));
+        match format {
+            DataFormat::Toml => crate::util::fs::read_toml(path),
+            DataFormat::Json => crate::util::fs::read_json(path),
+        }
+        .wrap_err("when importing quotes")
+    }
+
+    /// Saves the simple commands back to the config folder.
+    /// The existing file is edited, so its comments are kept.
+    pub fn save_commands(&self) -> color_eyre::Result<()> {
+        crate::util::fs::update_toml(&self.commands, self.path.join("commands.toml"))
+            .wrap_err("when saving commands")
     }
 }
 
@@ -59,3 +453,82 @@ fn read_or_default<T: serde::de::DeserializeOwned + Default>(
     let result = toml::from_str(&content).wrap_err("when parsing toml")?;
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_commands() {
+        let mut commands: SimpleCommands = toml::from_str(
+            r#"
+            cooldown = 5.0
+            [commands]
+            zeta = "last"
+            alpha = "first"
+            [commands.discord]
+            response = "Join the discord server!"
+            aliases = ["dc"]
+            cooldown = 10.0
+            "#,
+        )
+        .unwrap();
+
+        let add = |name: &str| CommandEdit::Add {
+            name: name.to_owned(),
+            response: "Hi, ${user}".to_owned(),
+        };
+        assert_eq!(
+            commands.apply(&add("DC")),
+            Err(CommandEditError::AlreadyExists("DC".to_owned()))
+        );
+        assert_eq!(commands.apply(&add("hi")), Ok(()));
+        let edit = CommandEdit::Edit {
+            name: "dc".to_owned(),
+            response: "discord.gg".to_owned(),
+        };
+        assert_eq!(commands.apply(&edit), Ok(()));
+        let delete = CommandEdit::Delete {
+            name: "alpha".to_owned(),
+        };
+        assert_eq!(commands.apply(&delete), Ok(()));
+        assert_eq!(
+            commands.apply(&delete),
+            Err(CommandEditError::NotFound("alpha".to_owned()))
+        );
+
+        let saved = toml::to_string_pretty(&commands).unwrap();
+        let loaded: SimpleCommands = toml::from_str(&saved).unwrap();
+        let names: Vec<&str> = loaded.commands.keys().map(String::as_str).collect();
+        // Commands written as tables are saved after the plain ones
+        assert_eq!(names, ["zeta", "hi", "discord"]);
+        let discord = &loaded.commands["discord"];
+        assert_eq!(discord.response, "discord.gg");
+        assert_eq!(discord.aliases, ["dc"]);
+        assert_eq!(discord.settings.cooldown, Some(10.0));
+    }
+
+    #[test]
+    fn test_timer_interval() {
+        let timers: TimersConfig = toml::from_str(
+            r#"
+            [[timers]]
+            name = "spam"
+            interval = 0.5
+            messages = ["a"]
+            [[timers]]
+            name = "socials"
+            interval = 900.0
+            messages = ["b"]
+            "#,
+        )
+        .unwrap();
+        let intervals: Vec<f64> = timers
+            .clamp_intervals()
+            .timers
+            .iter()
+            .map(|timer| timer.interval)
+            .collect();
+        assert_eq!(intervals, [MIN_TIMER_INTERVAL, 900.0]);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::config::{CommandEdit, CommandEditError, DataFormat};
+
 use super::{
     commands::{AuthorityLevel, CommandCall},
     *,
@@ -11,18 +13,114 @@ pub enum Action {
     },
     /// Reload the configuration file.
     ReloadConfig,
+    /// Show usage of the command, or list all commands if `None`.
+    /// Only commands available with the given authority are shown.
+    Help {
+        command: Option<String>,
+        authority: AuthorityLevel,
+    },
     /// Echo the message.
     Say(String),
+    /// Change the simple commands and save them to the config.
+    EditCommands(CommandEdit),
+    /// Show the settings and the response of the command.
+    CommandInfo {
+        command: String,
+    },
+    /// Show the value of the counter, or list all counters if `None`.
+    ShowCounter {
+        name: Option<String>,
+    },
+    /// Add the amount (can be negative) to the counter.
+    AddCounter {
+        name: String,
+        amount: i64,
+    },
+    SetCounter {
+        name: String,
+        value: i64,
+    },
+    ShowQuote(QuoteQuery),
+    AddQuote {
+        author: String,
+        text: String,
+    },
+    DeleteQuote {
+        id: u64,
+    },
+    /// Set the category that new quotes are added with, or clear it if `None`.
+    SetQuoteCategory(Option<String>),
+    ExportQuotes(DataFormat),
+    ImportQuotes(DataFormat),
+    SetTimer {
+        name: String,
+        enabled: bool,
+    },
+    /// List the timers and whether they are enabled.
+    ListTimers,
+    StartPoll {
+        question: String,
+        options: Vec<String>,
+        /// Duration in seconds.
+        duration: f64,
+    },
+    /// End the poll early and show the results.
+    EndPoll,
+    /// Start a new raffle that chat enters by typing the keyword.
+    OpenRaffle {
+        keyword: String,
+    },
+    /// Stop accepting entries to the raffle.
+    CloseRaffle,
+    /// Pick a winner of the raffle among those that have not won yet.
+    DrawRaffle,
+    EndRaffle,
+    /// Add the user to the viewer queue, with priority for subscribers if enabled.
+    JoinQueue {
+        user_id: String,
+        user: String,
+        authority: AuthorityLevel,
+    },
+    LeaveQueue {
+        user_id: String,
+        user: String,
+    },
+    /// Show the user's position in the viewer queue.
+    QueuePosition {
+        user_id: String,
+        user: String,
+    },
+    ShowQueue,
+    ClearQueue,
+    /// Allow or forbid viewers to join the queue.
+    SetQueueOpen(bool),
+    /// Take the next viewers out of the queue.
+    NextInQueue {
+        count: usize,
+    },
+    /// Vote in the poll for the option with the number, starting from 1.
+    Vote {
+        user_id: String,
+        option: usize,
+    },
 }
 
+/// Number of viewers listed by `!queue`.
+const QUEUE_SHOWN: usize = 10;
+
 impl Model {
     pub fn execute(&mut self, action: Action) -> Vec<AppAction> {
         log::debug!("Executing action: {:?}", action);
         match action {
             Action::HandleCommand { command, authority } => {
+                // The host is acting on behalf of the channel
+                let channel = self.channel_login.clone();
                 let call = CommandCall {
                     message: &command,
                     authority,
+                    user: &channel,
+                    user_id: &channel,
+                    channel: &channel,
                 };
                 self.handle_command_call(call)
             }
@@ -30,7 +128,304 @@ impl Model {
                 // Pass the action to the app, so the model is kept pure
                 vec![AppAction::ReloadConfig]
             }
+            Action::Help { command, authority } => {
+                let message = match command {
+                    Some(command) => self.commands.usage(&command, authority),
+                    None => self.commands.list(authority),
+                };
+                vec![AppAction::Say { message }]
+            }
             Action::Say(message) => vec![AppAction::Say { message }],
+            Action::EditCommands(edit) => {
+                if let CommandEdit::Add { name, .. } = &edit {
+                    // Simple commands are checked by the config, but not the others
+                    if self.commands.contains(name) {
+                        let message = CommandEditError::AlreadyExists(name.to_owned()).to_string();
+                        return vec![AppAction::Say { message }];
+                    }
+                }
+                // The config is owned by the app
+                vec![AppAction::EditCommands(edit)]
+            }
+            Action::CommandInfo { command } => {
+                let message = self.commands.info(&command);
+                vec![AppAction::Say { message }]
+            }
+            Action::ShowCounter { name } => {
+                let message = match name {
+                    Some(name) => format!("{}: {}", name, self.counters.get(&name)),
+                    None => {
+                        let counters: Vec<String> = self
+                            .counters
+                            .iter()
+                            .map(|(name, value)| format!("{name}: {value}"))
+                            .collect();
+                        if counters.is_empty() {
+                            "No counters yet".to_owned()
+                        } else {
+                            format!("Counters: {}", counters.join(", "))
+                        }
+                    }
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::AddCounter { name, amount } => {
+                let value = self.counters.add(&name, amount);
+                let message = format!("{name}: {value}");
+                vec![AppAction::SaveCounters, AppAction::Say { message }]
+            }
+            Action::SetCounter { name, value } => {
+                self.counters.set(&name, value);
+                let message = format!("{name}: {value}");
+                vec![AppAction::SaveCounters, AppAction::Say { message }]
+            }
+            Action::ShowQuote(query) => {
+                let message = match query {
+                    QuoteQuery::Random => self
+                        .quotes
+                        .random()
+                        .map_or_else(|| "No quotes yet".to_owned(), Quote::to_string),
+                    QuoteQuery::Id(id) => self
+                        .quotes
+                        .get(id)
+                        .map_or_else(|| format!("Quote #{id} not found"), Quote::to_string),
+                    QuoteQuery::Search(text) => match self.quotes.search(&text).as_slice() {
+                        [] => format!("No quotes found for {text:?}"),
+                        [quote] => quote.to_string(),
+                        [quote, rest @ ..] => {
+                            let ids: Vec<String> =
+                                rest.iter().map(|quote| format!("#{}", quote.id)).collect();
+                            format!("{} | Also found: {}", quote, ids.join(", "))
+                        }
+                    },
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::AddQuote { author, text } => {
+                let quote = self.quotes.add(text, author, quotes::current_date());
+                let message = format!("Added quote {quote}");
+                vec![AppAction::SaveQuotes, AppAction::Say { message }]
+            }
+            Action::DeleteQuote { id } => match self.quotes.remove(id) {
+                Some(_) => {
+                    let message = format!("Deleted quote #{id}");
+                    vec![AppAction::SaveQuotes, AppAction::Say { message }]
+                }
+                None => {
+                    let message = format!("Quote #{id} not found");
+                    vec![AppAction::Say { message }]
+                }
+            },
+            Action::SetQuoteCategory(category) => {
+                let message = match &category {
+                    Some(category) => format!("New quotes are added to {category}"),
+                    None => "New quotes are added without a category".to_owned(),
+                };
+                self.quotes.category = category;
+                vec![AppAction::SaveQuotes, AppAction::Say { message }]
+            }
+            // File access is left to the app
+            Action::ExportQuotes(format) => {
+                if self.quotes.is_empty() {
+                    let message = "No quotes to export".to_owned();
+                    return vec![AppAction::Say { message }];
+                }
+                vec![AppAction::ExportQuotes(format)]
+            }
+            Action::SetTimer { name, enabled } => {
+                let message = if !self.timers.set_enabled(&name, enabled) {
+                    format!("Unknown timer: {name}")
+                } else if enabled {
+                    format!("Timer {name} enabled")
+                } else {
+                    format!("Timer {name} disabled")
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::StartPoll {
+                question,
+                options,
+                duration,
+            } => {
+                if self.poll.is_some() {
+                    let message = "A poll is already running, end it with !poll end".to_owned();
+                    return vec![AppAction::Say { message }];
+                }
+                let poll = Poll::new(question, options, duration);
+                let message = poll.announcement();
+                self.poll = Some(poll);
+                vec![AppAction::Say { message }]
+            }
+            Action::EndPoll => {
+                let message = match self.poll.take() {
+                    Some(poll) => poll.summary(),
+                    None => "No poll is running".to_owned(),
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::Vote { user_id, option } => {
+                // Votes are shown in the poll panel instead of the chat
+                if let Some(poll) = &mut self.poll {
+                    poll.vote(&user_id, option);
+                }
+                vec![]
+            }
+            Action::OpenRaffle { keyword } => {
+                if self.raffle.is_some() {
+                    let message = "A raffle is already running, end it with !raffle end".to_owned();
+                    return vec![AppAction::Say { message }];
+                }
+                let message = format!("A raffle has started! Type {keyword} to enter");
+                self.raffle = Some(Raffle::new(keyword));
+                vec![AppAction::Say { message }]
+            }
+            Action::CloseRaffle => {
+                let message = match &mut self.raffle {
+                    Some(raffle) => {
+                        raffle.open = false;
+                        format!(
+                            "The raffle is closed with {} entrants",
+                            raffle.entrants.len()
+                        )
+                    }
+                    None => "No raffle is running".to_owned(),
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::DrawRaffle => {
+                let message = match &mut self.raffle {
+                    Some(raffle) => match raffle.draw(&mut rand::thread_rng()) {
+                        Some(winner) => format!("@{winner} won the raffle!"),
+                        None => "No entrants left to draw".to_owned(),
+                    },
+                    None => "No raffle is running".to_owned(),
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::EndRaffle => {
+                let message = match self.raffle.take() {
+                    Some(raffle) if raffle.winners.is_empty() => {
+                        "The raffle has ended without winners".to_owned()
+                    }
+                    Some(raffle) => {
+                        format!(
+                            "The raffle has ended, winners: {}",
+                            raffle
+                                .winners
+                                .iter()
+                                .map(|winner| winner.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    }
+                    None => "No raffle is running".to_owned(),
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::JoinQueue {
+                user_id,
+                user,
+                authority,
+            } => {
+                let priority = self.queue_config.subscriber_priority
+                    && authority >= AuthorityLevel::Subscriber;
+                match self.queue.join(&user_id, &user, priority) {
+                    Ok(position) => {
+                        let message = format!("@{user} joined the queue at position {position}");
+                        vec![AppAction::SaveQueue, AppAction::Say { message }]
+                    }
+                    Err(JoinError::Closed) => {
+                        let message = format!("@{user}, the queue is closed");
+                        vec![AppAction::Say { message }]
+                    }
+                    Err(JoinError::AlreadyJoined(position)) => {
+                        let message =
+                            format!("@{user}, you are already in the queue at position {position}");
+                        vec![AppAction::Say { message }]
+                    }
+                }
+            }
+            Action::LeaveQueue { user_id, user } => {
+                if self.queue.leave(&user_id) {
+                    let message = format!("@{user} left the queue");
+                    vec![AppAction::SaveQueue, AppAction::Say { message }]
+                } else {
+                    let message = format!("@{user}, you are not in the queue");
+                    vec![AppAction::Say { message }]
+                }
+            }
+            Action::QueuePosition { user_id, user } => {
+                let message = match self.queue.position(&user_id) {
+                    Some(position) => format!(
+                        "@{user}, you are at position {position} of {}",
+                        self.queue.len()
+                    ),
+                    None => format!("@{user}, you are not in the queue"),
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::ShowQueue => {
+                let state = if self.queue.open { "open" } else { "closed" };
+                let names: Vec<&str> = self
+                    .queue
+                    .iter()
+                    .take(QUEUE_SHOWN)
+                    .map(|entry| entry.name.as_str())
+                    .collect();
+                let mut message = format!("Queue ({}, {} viewers)", state, self.queue.len());
+                if !names.is_empty() {
+                    message += &format!(": {}", names.join(", "));
+                }
+                if self.queue.len() > QUEUE_SHOWN {
+                    message += ", ...";
+                }
+                vec![AppAction::Say { message }]
+            }
+            Action::ClearQueue => {
+                self.queue.clear();
+                let message = "The queue has been cleared".to_owned();
+                vec![AppAction::SaveQueue, AppAction::Say { message }]
+            }
+            Action::SetQueueOpen(open) => {
+                self.queue.open = open;
+                let message = if open {
+                    "The queue is open, type !join to join".to_owned()
+                } else {
+                    "The queue is closed".to_owned()
+                };
+                vec![AppAction::SaveQueue, AppAction::Say { message }]
+            }
+            Action::NextInQueue { count } => {
+                let next: Vec<String> = self
+                    .queue
+                    .next(count)
+                    .into_iter()
+                    .map(|entry| format!("@{}", entry.name))
+                    .collect();
+                if next.is_empty() {
+                    let message = "The queue is empty".to_owned();
+                    return vec![AppAction::Say { message }];
+                }
+                let message = format!("Up next: {}", next.join(", "));
+                vec![AppAction::SaveQueue, AppAction::Say { message }]
+            }
+            Action::ListTimers => {
+                let timers: Vec<String> = self
+                    .timers
+                    .iter()
+                    .map(|timer| {
+                        let state = if timer.enabled { "on" } else { "off" };
+                        format!("{} ({})", timer.config.name, state)
+                    })
+                    .collect();
+                let message = if timers.is_empty() {
+                    "No timers configured".to_owned()
+                } else {
+                    format!("Timers: {}", timers.join(", "))
+                };
+                vec![AppAction::Say { message }]
+            }
+            Action::ImportQuotes(format) => vec![AppAction::ImportQuotes(format)],
         }
     }
 }
@@ -1,69 +1,478 @@
-use super::{parse::ArgsError, *};
+use crate::config::{CommandEdit, DataFormat};
+use crate::model::{Poll, QuoteQuery};
+
+use super::{
+    parse::ArgsError,
+    template::{self, TemplateContext},
+    *,
+};
 
 /// Command callable actions that might require extra arguments.
-/// Arguments are refered to in the docs like `$0` (for the first argument).
-#[derive(Debug, Clone)]
+/// Hardcoded commands are described by the `#[command]` attributes,
+/// and their arguments are parsed into the variant's fields.
+#[derive(Debug, Clone, Command)]
 pub enum CommandAction {
     /// Reload the configuration file.
+    #[command("!reload")]
     ReloadConfig,
-    /// Echo the message.
+    /// Show usage of the command, or list all available commands.
+    #[command("!help", "!commands")]
+    Help {
+        command: Option<String>,
+    },
+    /// Send the response, rendered from a template, see [template::render].
     Say(String),
-    /// Say hello to $0.
-    Hello,
-    /// Say bye to $0.
-    Bye,
-    /// Say good night to $0.
-    GoodNight,
+    /// Say hello to `name`.
+    #[command("!hello")]
+    Hello {
+        #[argument(word, default = "everyone")]
+        name: String,
+    },
+    /// Say bye to `name`.
+    #[command("!bye")]
+    Bye {
+        #[argument(word, default = "everyone")]
+        name: String,
+    },
+    /// Say good night to `name`.
+    #[command("!gn")]
+    GoodNight {
+        #[argument(word, default = "everyone")]
+        name: String,
+    },
+    /// Add a simple command that sends the response.
+    #[command("!addcom")]
+    AddCommand {
+        name: String,
+        #[argument(line)]
+        response: String,
+    },
+    /// Change the response of a simple command.
+    #[command("!editcom")]
+    EditCommand {
+        name: String,
+        #[argument(line)]
+        response: String,
+    },
+    /// Delete a simple command.
+    #[command("!delcom")]
+    DeleteCommand {
+        name: String,
+    },
+    /// Show the settings and the response of a command.
+    #[command("!cmdinfo")]
+    CommandInfo {
+        name: String,
+    },
+    /// Show the value of a counter, or list all counters.
+    #[command("!count")]
+    ShowCounter {
+        name: Option<String>,
+    },
+    /// Increase a counter by the amount.
+    #[command("!countinc")]
+    IncrementCounter {
+        name: String,
+        #[argument(default = 1)]
+        amount: i64,
+    },
+    /// Decrease a counter by the amount.
+    #[command("!countdec")]
+    DecrementCounter {
+        name: String,
+        #[argument(default = 1)]
+        amount: i64,
+    },
+    /// Set a counter to the value.
+    #[command("!countset")]
+    SetCounter {
+        name: String,
+        value: i64,
+    },
+    /// Show a quote, see [CommandAction::branching_nodes].
+    Quote(QuoteQuery),
+    /// Add a quote said by `author`, or by the streamer if not specified.
+    #[command("!addquote")]
+    AddQuote {
+        #[argument(regex("@[a-zA-Z0-9_]+"), optional)]
+        author: Option<String>,
+        #[argument(line)]
+        text: String,
+    },
+    #[command("!delquote")]
+    DeleteQuote {
+        #[argument(int(1, i64::MAX))]
+        id: i64,
+    },
+    /// Set the category that new quotes are added with, or clear it.
+    #[command("!quotecategory")]
+    SetQuoteCategory {
+        #[argument(line, optional)]
+        category: Option<String>,
+    },
+    /// Write all quotes to a file in the config folder.
+    #[command("!exportquotes")]
+    ExportQuotes {
+        #[argument(choice("toml", "json"))]
+        format: String,
+    },
+    /// Add the quotes from a file in the config folder.
+    #[command("!importquotes")]
+    ImportQuotes {
+        #[argument(choice("toml", "json"))]
+        format: String,
+    },
+    /// Enable or disable a timer.
+    #[command("!timer")]
+    SetTimer {
+        #[argument(choice("on", "off"))]
+        state: String,
+        name: String,
+    },
+    /// List the timers and whether they are enabled.
+    #[command("!timers")]
+    ListTimers,
+    /// Start a poll, see [CommandAction::branching_nodes].
+    /// The options are separated by `|` and may end with the duration of the poll.
+    StartPoll,
+    /// End the poll early and show the results.
+    EndPoll,
+    /// Start a raffle that chat enters by typing the keyword,
+    /// see [CommandAction::branching_nodes].
+    OpenRaffle,
+    /// Stop accepting entries to the raffle.
+    CloseRaffle,
+    /// Pick a winner of the raffle among those that have not won yet.
+    DrawRaffle,
+    /// Remove the raffle.
+    EndRaffle,
+    #[command("!join")]
+    JoinQueue,
+    #[command("!leave")]
+    LeaveQueue,
+    /// Show the caller's position in the viewer queue.
+    #[command("!position")]
+    QueuePosition,
+    /// Show the viewer queue, see [CommandAction::branching_nodes].
+    ShowQueue,
+    ClearQueue,
+    /// Allow viewers to join the queue.
+    OpenQueue,
+    CloseQueue,
+    /// Take the next `count` viewers out of the queue.
+    #[command("!next")]
+    NextInQueue {
+        #[argument(int(1, 50), default = 1)]
+        count: i64,
+    },
+    /// Vote in the poll for the option with the number.
+    #[command("!vote")]
+    Vote {
+        #[argument(int(1, 100))]
+        option: i64,
+    },
 }
 
-// macro_rules! extract_args {
-//     // Extract a specific length list
-//     ($from:expr, [$($in:tt),*$(...)?]) => {
-//         extract_args!($from, [$($in),*$(...)?] -> [])
-//     };
-//     // Extract an empty list
-//     ($from:expr, [] -> [$(out:tt),*]) => {
-//         if $from.is_empty() {
-//             [$(out),*]
-//         } else {
-//             return Err(ArgsError::TooMany)
-//         }
-//     };
-// }
-
 impl CommandAction {
-    pub fn into_action(self, mut arguments: Vec<String>) -> Result<Action, ArgsError> {
+    /// Hardcoded commands that branch after the first literal,
+    /// which cannot be described by the `#[command]` attributes.
+    pub fn branching_nodes() -> Vec<CommandNode<Self>> {
+        vec![
+            CommandBuilder::new().literal(["!quote"]).split([
+                CommandBuilder::new().finalize(true, Self::Quote(QuoteQuery::Random)),
+                CommandBuilder::new()
+                    .integer(Some(1), None)
+                    .named("id")
+                    .finalize(true, Self::Quote(QuoteQuery::Id(0))),
+                CommandBuilder::new()
+                    .literal(["search"])
+                    .line()
+                    .named("text")
+                    .finalize(true, Self::Quote(QuoteQuery::Search(String::new()))),
+            ]),
+            CommandBuilder::new().literal(["!poll"]).split([
+                CommandBuilder::new()
+                    .literal(["start"])
+                    .quoted()
+                    .named("question")
+                    .line()
+                    .named("options")
+                    .finalize(true, Self::StartPoll),
+                CommandBuilder::new()
+                    .literal(["end"])
+                    .finalize(true, Self::EndPoll),
+            ]),
+            CommandBuilder::new().literal(["!raffle"]).split([
+                CommandBuilder::new()
+                    .literal(["open"])
+                    .word()
+                    .named("keyword")
+                    .finalize(true, Self::OpenRaffle),
+                CommandBuilder::new()
+                    .literal(["close"])
+                    .finalize(true, Self::CloseRaffle),
+                CommandBuilder::new()
+                    .literal(["draw"])
+                    .finalize(true, Self::DrawRaffle),
+                CommandBuilder::new()
+                    .literal(["end"])
+                    .finalize(true, Self::EndRaffle),
+            ]),
+            CommandBuilder::new().literal(["!queue"]).split([
+                CommandBuilder::new().finalize(true, Self::ShowQueue),
+                CommandBuilder::new()
+                    .literal(["clear"])
+                    .finalize(true, Self::ClearQueue),
+                CommandBuilder::new()
+                    .literal(["open"])
+                    .finalize(true, Self::OpenQueue),
+                CommandBuilder::new()
+                    .literal(["close"])
+                    .finalize(true, Self::CloseQueue),
+            ]),
+        ]
+    }
+
+    /// Authority level required to call the hardcoded command.
+    pub fn authority(&self) -> AuthorityLevel {
         match self {
+            CommandAction::ReloadConfig
+            | CommandAction::ExportQuotes { .. }
+            | CommandAction::ImportQuotes { .. }
+            | CommandAction::OpenRaffle
+            | CommandAction::CloseRaffle
+            | CommandAction::DrawRaffle
+            | CommandAction::EndRaffle => AuthorityLevel::Broadcaster,
+            CommandAction::AddCommand { .. }
+            | CommandAction::EditCommand { .. }
+            | CommandAction::DeleteCommand { .. }
+            | CommandAction::CommandInfo { .. }
+            | CommandAction::IncrementCounter { .. }
+            | CommandAction::DecrementCounter { .. }
+            | CommandAction::SetCounter { .. }
+            | CommandAction::AddQuote { .. }
+            | CommandAction::DeleteQuote { .. }
+            | CommandAction::SetQuoteCategory { .. }
+            | CommandAction::SetTimer { .. }
+            | CommandAction::ListTimers
+            | CommandAction::StartPoll
+            | CommandAction::EndPoll
+            | CommandAction::ClearQueue
+            | CommandAction::OpenQueue
+            | CommandAction::CloseQueue
+            | CommandAction::NextInQueue { .. } => AuthorityLevel::Moderator,
+            _ => AuthorityLevel::Viewer,
+        }
+    }
+
+    /// Cooldown of the hardcoded command in seconds.
+    pub fn cooldown(&self) -> f64 {
+        match self {
+            CommandAction::ReloadConfig
+            | CommandAction::Say(_)
+            | CommandAction::AddCommand { .. }
+            | CommandAction::EditCommand { .. }
+            | CommandAction::DeleteCommand { .. }
+            | CommandAction::CommandInfo { .. }
+            | CommandAction::IncrementCounter { .. }
+            | CommandAction::DecrementCounter { .. }
+            | CommandAction::SetCounter { .. }
+            | CommandAction::AddQuote { .. }
+            | CommandAction::DeleteQuote { .. }
+            | CommandAction::SetQuoteCategory { .. }
+            | CommandAction::ExportQuotes { .. }
+            | CommandAction::ImportQuotes { .. }
+            | CommandAction::SetTimer { .. }
+            | CommandAction::ListTimers
+            | CommandAction::StartPoll
+            | CommandAction::EndPoll
+            | CommandAction::Vote { .. }
+            | CommandAction::OpenRaffle
+            | CommandAction::CloseRaffle
+            | CommandAction::DrawRaffle
+            | CommandAction::EndRaffle
+            | CommandAction::ClearQueue
+            | CommandAction::OpenQueue
+            | CommandAction::CloseQueue
+            | CommandAction::NextInQueue { .. } => 0.0,
+            CommandAction::JoinQueue
+            | CommandAction::LeaveQueue
+            | CommandAction::QueuePosition
+            | CommandAction::ShowQueue => 10.0,
+            CommandAction::ShowCounter { .. } => 5.0,
+            CommandAction::Quote(_) => 10.0,
+            CommandAction::Help { .. } => 10.0,
+            CommandAction::Hello { .. }
+            | CommandAction::Bye { .. }
+            | CommandAction::GoodNight { .. } => 30.0,
+        }
+    }
+
+    /// Which calls of the hardcoded command share a cooldown.
+    pub fn cooldown_scope(&self) -> CooldownScope {
+        match self {
+            CommandAction::JoinQueue | CommandAction::LeaveQueue | CommandAction::QueuePosition => {
+                CooldownScope::User
+            }
+            _ => CooldownScope::Args,
+        }
+    }
+
+    pub fn into_action(self, context: TemplateContext) -> Result<Action, ArgsError> {
+        let arguments = context.arguments;
+        let action = self.with_arguments(arguments).ok_or(ArgsError::Mismatch)?;
+        match action {
             CommandAction::ReloadConfig => Ok(Action::ReloadConfig),
-            CommandAction::Say(msg) => Ok(Action::Say(msg)),
-            CommandAction::Hello => {
-                verify_args(&arguments, 1, true)?;
-                let name = arguments.pop().unwrap();
+            CommandAction::Help { command } => Ok(Action::Help {
+                command,
+                authority: context.call.authority,
+            }),
+            CommandAction::Say(template) => {
+                let msg = template::render(&template, &context)?;
+                Ok(Action::Say(msg))
+            }
+            CommandAction::Hello { name } => {
                 let msg = format!("Hi, {name} ^^");
                 Ok(Action::Say(msg))
             }
-            CommandAction::Bye => {
-                verify_args(&arguments, 1, true)?;
-                let name = arguments.pop().unwrap();
+            CommandAction::Bye { name } => {
                 let msg = format!("cya, {name}!");
                 Ok(Action::Say(msg))
             }
-            CommandAction::GoodNight => {
-                verify_args(&arguments, 1, true)?;
-                let name = arguments.pop().unwrap();
+            CommandAction::GoodNight { name } => {
                 let msg = format!("Good night, {name} ^^");
                 Ok(Action::Say(msg))
             }
+            CommandAction::AddCommand { name, response } => {
+                let name = command_name(&name);
+                Ok(edit_action(CommandEdit::Add { name, response }))
+            }
+            CommandAction::EditCommand { name, response } => {
+                let name = command_name(&name);
+                Ok(edit_action(CommandEdit::Edit { name, response }))
+            }
+            CommandAction::DeleteCommand { name } => {
+                let name = command_name(&name);
+                Ok(Action::EditCommands(CommandEdit::Delete { name }))
+            }
+            CommandAction::CommandInfo { name } => Ok(Action::CommandInfo {
+                command: command_name(&name),
+            }),
+            CommandAction::ShowCounter { name } => Ok(Action::ShowCounter { name }),
+            CommandAction::IncrementCounter { name, amount } => {
+                Ok(Action::AddCounter { name, amount })
+            }
+            CommandAction::DecrementCounter { name, amount } => Ok(Action::AddCounter {
+                name,
+                amount: amount.saturating_neg(),
+            }),
+            CommandAction::SetCounter { name, value } => Ok(Action::SetCounter { name, value }),
+            CommandAction::Quote(query) => {
+                // Not filled by `with_arguments`, as the tree is built by hand
+                let query = match query {
+                    QuoteQuery::Random => QuoteQuery::Random,
+                    QuoteQuery::Id(_) => {
+                        let id: i64 = arguments.get("id").ok_or(ArgsError::Mismatch)?;
+                        QuoteQuery::Id(id.unsigned_abs())
+                    }
+                    QuoteQuery::Search(_) => {
+                        QuoteQuery::Search(arguments.get("text").ok_or(ArgsError::Mismatch)?)
+                    }
+                };
+                Ok(Action::ShowQuote(query))
+            }
+            CommandAction::AddQuote { author, text } => {
+                let author = match author {
+                    Some(author) => author.trim_start_matches('@').to_owned(),
+                    None => context.call.channel.to_owned(),
+                };
+                Ok(Action::AddQuote { author, text })
+            }
+            CommandAction::DeleteQuote { id } => Ok(Action::DeleteQuote {
+                id: id.unsigned_abs(),
+            }),
+            CommandAction::SetQuoteCategory { category } => Ok(Action::SetQuoteCategory(category)),
+            CommandAction::ExportQuotes { format } => {
+                let format = DataFormat::from_name(&format).ok_or(ArgsError::Mismatch)?;
+                Ok(Action::ExportQuotes(format))
+            }
+            CommandAction::ImportQuotes { format } => {
+                let format = DataFormat::from_name(&format).ok_or(ArgsError::Mismatch)?;
+                Ok(Action::ImportQuotes(format))
+            }
+            CommandAction::SetTimer { state, name } => Ok(Action::SetTimer {
+                name,
+                enabled: state == "on",
+            }),
+            CommandAction::ListTimers => Ok(Action::ListTimers),
+            CommandAction::StartPoll => {
+                // Not filled by `with_arguments`, as the tree is built by hand
+                let question = arguments.get("question").ok_or(ArgsError::Mismatch)?;
+                let options: String = arguments.get("options").ok_or(ArgsError::Mismatch)?;
+                let Some((options, duration)) = Poll::parse_options(&options) else {
+                    let msg = "A poll needs at least 2 options separated by |".to_owned();
+                    return Ok(Action::Say(msg));
+                };
+                Ok(Action::StartPoll {
+                    question,
+                    options,
+                    duration: duration.unwrap_or(DEFAULT_POLL_DURATION),
+                })
+            }
+            CommandAction::EndPoll => Ok(Action::EndPoll),
+            CommandAction::OpenRaffle => {
+                // Not filled by `with_arguments`, as the tree is built by hand
+                let keyword = arguments.get("keyword").ok_or(ArgsError::Mismatch)?;
+                Ok(Action::OpenRaffle { keyword })
+            }
+            CommandAction::CloseRaffle => Ok(Action::CloseRaffle),
+            CommandAction::DrawRaffle => Ok(Action::DrawRaffle),
+            CommandAction::EndRaffle => Ok(Action::EndRaffle),
+            CommandAction::JoinQueue => Ok(Action::JoinQueue {
+                user_id: context.call.user_id.to_owned(),
+                user: context.call.user.to_owned(),
+                authority: context.call.authority,
+            }),
+            CommandAction::LeaveQueue => Ok(Action::LeaveQueue {
+                user_id: context.call.user_id.to_owned(),
+                user: context.call.user.to_owned(),
+            }),
+            CommandAction::QueuePosition => Ok(Action::QueuePosition {
+                user_id: context.call.user_id.to_owned(),
+                user: context.call.user.to_owned(),
+            }),
+            CommandAction::ShowQueue => Ok(Action::ShowQueue),
+            CommandAction::ClearQueue => Ok(Action::ClearQueue),
+            CommandAction::OpenQueue => Ok(Action::SetQueueOpen(true)),
+            CommandAction::CloseQueue => Ok(Action::SetQueueOpen(false)),
+            CommandAction::NextInQueue { count } => Ok(Action::NextInQueue {
+                count: count.unsigned_abs() as usize,
+            }),
+            CommandAction::Vote { option } => Ok(Action::Vote {
+                user_id: context.call.user_id.to_owned(),
+                option: option.unsigned_abs() as usize,
+            }),
         }
     }
 }
 
-fn verify_args(args: &[String], len: usize, exact: bool) -> Result<(), ArgsError> {
-    if args.len() < len {
-        Err(ArgsError::NotEnough)
-    } else if exact && args.len() > len {
-        Err(ArgsError::TooMany)
-    } else {
-        Ok(())
+/// Duration of a poll in seconds, if not specified.
+const DEFAULT_POLL_DURATION: f64 = 60.0;
+
+/// Name of a simple command as written in the config, without the `!` prefix.
+fn command_name(name: &str) -> String {
+    name.trim_start_matches('!').to_lowercase()
+}
+
+/// Refuses to save a malformed response, so that the mistake is noticed in chat.
+fn edit_action(edit: CommandEdit) -> Action {
+    match &edit {
+        CommandEdit::Add { response, .. } | CommandEdit::Edit { response, .. } => {
+            if let Err(err) = template::validate(response) {
+                return Action::Say(format!("Invalid response: {err}"));
+            }
+        }
+        CommandEdit::Delete { .. } => {}
     }
+    Action::EditCommands(edit)
 }
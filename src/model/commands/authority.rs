@@ -1,5 +1,9 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AuthorityLevel {
+    #[default]
     Viewer,
     Subscriber,
     Moderator,
@@ -8,6 +12,19 @@ pub enum AuthorityLevel {
     Host,
 }
 
+impl std::fmt::Display for AuthorityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AuthorityLevel::Viewer => "viewer",
+            AuthorityLevel::Subscriber => "subscriber",
+            AuthorityLevel::Moderator => "moderator",
+            AuthorityLevel::Broadcaster => "broadcaster",
+            AuthorityLevel::Host => "host",
+        };
+        write!(f, "{name}")
+    }
+}
+
 impl AuthorityLevel {
     pub fn from_badges(badges: &[twitch_irc::message::Badge]) -> Self {
         badges
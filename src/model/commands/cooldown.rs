@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Which calls of a command share a cooldown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownScope {
+    /// Any call puts the whole command on cooldown.
+    Global,
+    /// Every user has their own cooldown.
+    User,
+    /// Every set of arguments has its own cooldown (see [Arguments::key]).
+    #[default]
+    Args,
+    /// Every user has their own cooldown for each set of arguments.
+    UserArgs,
+}
+
+/// Identifies calls that share a cooldown.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CooldownKey {
+    user: Option<String>,
+    arguments: Option<Vec<String>>,
+}
+
+impl CooldownScope {
+    pub fn key(&self, call: &CommandCall, arguments: &Arguments) -> CooldownKey {
+        let user = || Some(call.user_id.to_owned());
+        let arguments = || Some(arguments.key());
+        match self {
+            CooldownScope::Global => CooldownKey {
+                user: None,
+                arguments: None,
+            },
+            CooldownScope::User => CooldownKey {
+                user: user(),
+                arguments: None,
+            },
+            CooldownScope::Args => CooldownKey {
+                user: None,
+                arguments: arguments(),
+            },
+            CooldownScope::UserArgs => CooldownKey {
+                user: user(),
+                arguments: arguments(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_key() {
+        let call = |user, user_id| CommandCall {
+            message: "!join",
+            authority: AuthorityLevel::Viewer,
+            user,
+            user_id,
+            channel: "streamer",
+        };
+        let key = |call| CooldownScope::User.key(&call, &Arguments::default());
+        // Renaming does not reset the cooldown
+        assert_eq!(key(call("Alice", "1")), key(call("Alicia", "1")));
+        assert_ne!(key(call("Alice", "1")), key(call("Alice", "2")));
+    }
+}
@@ -0,0 +1,74 @@
+use super::*;
+
+impl Commands {
+    /// Whether there is a command with the given name, ignoring case.
+    /// The name can be specified with or without the `!` prefix.
+    pub fn contains(&self, command: &str) -> bool {
+        self.find(command).is_some()
+    }
+
+    fn find(&self, command: &str) -> Option<&CommandTree> {
+        self.iter().find(|tree| is_named(tree, command))
+    }
+
+    /// Describes the names, settings and response of the command.
+    pub fn info(&self, command: &str) -> String {
+        let Some(tree) = self.find(command) else {
+            return format!("Unknown command: !{}", command.trim_start_matches('!'));
+        };
+        let mut info = format!(
+            "{} | authority: {}, cooldown: {}s, used {} times",
+            tree.names().join(", "),
+            tree.authority(),
+            tree.cooldown(),
+            tree.uses()
+        );
+        match tree.root().final_values().as_slice() {
+            [CommandAction::Say(response)] => {
+                info += &format!(" | response: {response}");
+            }
+            _ => {
+                info += &format!(" | usage: {}", tree.usage().join("; "));
+            }
+        }
+        info
+    }
+
+    /// Lists all commands available with the given authority.
+    pub fn list(&self, authority: AuthorityLevel) -> String {
+        let mut names: Vec<&str> = self
+            .iter()
+            .filter(|command| command.is_authorized(authority))
+            .filter_map(|command| command.names().first())
+            .map(|name| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        format!("Available commands: {}", names.join(", "))
+    }
+
+    /// Describes how to use the command, if it is available with the given authority.
+    /// The command can be specified with or without the `!` prefix.
+    pub fn usage(&self, command: &str, authority: AuthorityLevel) -> String {
+        let command = command.trim_start_matches('!');
+        let usage: Vec<String> = self
+            .iter()
+            .filter(|tree| tree.is_authorized(authority))
+            .filter(|tree| is_named(tree, command))
+            .flat_map(|tree| tree.usage())
+            .collect();
+        if usage.is_empty() {
+            format!("Unknown command: !{command}")
+        } else {
+            format!("Usage: {}", usage.join("; "))
+        }
+    }
+}
+
+/// Whether the command has the name, ignoring case and the `!` prefix.
+fn is_named(tree: &CommandTree, command: &str) -> bool {
+    let command = command.trim_start_matches('!');
+    tree.names()
+        .iter()
+        .any(|name| name.trim_start_matches('!').eq_ignore_ascii_case(command))
+}
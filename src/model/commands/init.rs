@@ -3,50 +3,175 @@ use super::*;
 impl Commands {
     /// Reloads `configured` command list.
     pub fn reload(&mut self, config: &SimpleCommands) {
-        self.configured = config
-            .commands
+        let simple = config.commands.iter().map(|(name, command)| {
+            let literals = std::iter::once(name)
+                .chain(&command.aliases)
+                .map(|name| format!("!{}", name));
+            // Accept the rest of the message if the response refers to it
+            let expects_empty_message = !template::uses_arguments(&command.response);
+            CommandTree::new(
+                CommandBuilder::new()
+                    .literal(literals)
+                    .ignore_case()
+                    .finalize(
+                        expects_empty_message,
+                        CommandAction::Say(command.response.to_owned()),
+                    ),
+            )
+            .with_settings(&command.settings, config)
+        });
+
+        let mut subtrees = Subtrees::new();
+        for (name, nodes) in &config.subtrees {
+            let nodes = nodes
+                .iter()
+                .map(|node| node.clone().map(&mut CommandAction::Say))
+                .collect();
+            subtrees.insert(name, nodes);
+        }
+        let (subtrees, errors) = subtrees.link_partial();
+        for (name, err) in errors {
+            log::error!(
+                "Failed to link command subtree {:?}, leaving it out: {}",
+                name,
+                err
+            );
+        }
+
+        let trees = config.trees.iter().map(|tree| {
+            let mut root = tree.root.clone().map(&mut CommandAction::Say);
+            if let Err(err) = root.link(&subtrees) {
+                log::error!("Failed to link command {:?}: {}", root.usage(), err);
+            }
+            CommandTree::new(root).with_settings(&tree.settings, config)
+        });
+
+        // Hardcoded commands follow the defaults
+        self.hardcoded = std::mem::take(&mut self.hardcoded)
+            .into_iter()
+            .map(|command| {
+                command
+                    .with_cooldown_bypass(config.cooldown_bypass)
+                    .with_feedback(config.feedback, config.feedback_cooldown)
+            })
+            .collect();
+
+        // Keep counting the uses of the commands that are still there
+        let uses: BTreeMap<String, u64> = self
+            .configured
             .iter()
-            .map(|(command, response)| {
-                CommandTree::new(
-                    CommandBuilder::new()
-                        .literal([format!("!{}", command)])
-                        .finalize(true, CommandAction::Say(response.to_owned())),
-                )
-                .with_cooldown(config.cooldown)
+            .filter_map(|command| Some((command.names().first()?.clone(), command.uses())))
+            .collect();
+        self.configured = simple
+            .chain(trees)
+            .map(|command| {
+                let uses = command.names().first().and_then(|name| uses.get(name));
+                match uses {
+                    Some(&uses) => command.with_uses(uses),
+                    None => command,
+                }
             })
             .collect();
+        self.suggestion_cooldown = config.feedback_cooldown;
+        self.dispatcher = Dispatcher::new(self.iter().map(|command| command.root()));
+        self.validate();
+    }
+
+    /// Logs ambiguous and unreachable commands, and malformed response templates.
+    fn validate(&self) {
+        // Hardcoded commands are validated as declared, before they are split
+        let hardcoded: Vec<_> = Self::hardcoded_roots().collect();
+        let roots = self
+            .configured
+            .iter()
+            .map(|command| command.root())
+            .chain(&hardcoded);
+        let warnings = minmands::validate_trees(roots);
+        for warning in &warnings {
+            log::warn!("Command {}", warning);
+        }
+
+        for command in &self.configured {
+            for action in command.root().final_values() {
+                if let CommandAction::Say(response) = action {
+                    if let Err(err) = template::validate(response) {
+                        log::warn!("Command {:?}: {} in {:?}", command.names(), err, response);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Hardcoded commands as they are declared.
+    pub(super) fn hardcoded_roots() -> impl Iterator<Item = CommandNode<CommandAction>> {
+        CommandAction::command_nodes()
+            .into_iter()
+            .chain(CommandAction::branching_nodes())
+            .map(|root| root.with_ignore_case())
     }
 
     pub fn init(config: &SimpleCommands) -> Self {
-        let system = [CommandTree::new(command!(
-            "!reload";
-            true, CommandAction::ReloadConfig
-        ))
-        .with_authority(AuthorityLevel::Broadcaster)];
-
-        let greetings = [
-            ("!hello", CommandAction::Hello),
-            ("!bye", CommandAction::Bye),
-            ("!gn", CommandAction::GoodNight),
-        ]
-        .into_iter()
-        .map(|(command, action)| {
-            CommandTree::new(
-                CommandBuilder::new()
-                    .literal([command])
-                    .word()
-                    .finalize(true, action),
-            )
-            .with_cooldown(30.0)
+        let roots = Self::hardcoded_roots().flat_map(split_by_settings);
+        let hardcoded = roots.map(|root| {
+            let action = root.final_values().first().map(|&action| action.clone());
+            let tree = CommandTree::new(root);
+            match action {
+                Some(action) => tree
+                    .with_authority(action.authority())
+                    .with_cooldown(action.cooldown())
+                    .with_cooldown_scope(action.cooldown_scope()),
+                None => tree,
+            }
         });
 
-        let hardcoded = iter_tools::chain![system, greetings];
-
         let mut commands = Self {
-            configured: vec![], // Set on reload
-            hardcoded: hardcoded.collect(),
+            configured: vec![],                // Set on reload
+            hardcoded: hardcoded.collect(),    // Settings are applied on reload
+            dispatcher: Dispatcher::default(), // Set on reload
+            suggestion_cooldown: 0.0,          // Set on reload
+            suggestion_timer: 0.0,
         };
         commands.reload(config);
         commands
     }
 }
+
+/// Splits the branches after the first literal into separate trees
+/// by the authority and cooldown of their actions, so that e.g. viewers
+/// can see the `!queue` while only moderators can clear it.
+fn split_by_settings(root: CommandNode<CommandAction>) -> Vec<CommandNode<CommandAction>> {
+    let CommandNode::Literal {
+        literals,
+        ignore_case,
+        child_nodes,
+    } = root
+    else {
+        return vec![root];
+    };
+    // Branches without a final value stay together
+    let settings = |node: &CommandNode<CommandAction>| {
+        node.final_values().first().map(|action| {
+            (
+                action.authority(),
+                action.cooldown(),
+                action.cooldown_scope(),
+            )
+        })
+    };
+    let mut groups: Vec<(_, Vec<_>)> = Vec::new();
+    for child_node in child_nodes {
+        let key = settings(&child_node);
+        match groups.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, group)) => group.push(child_node),
+            None => groups.push((key, vec![child_node])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, child_nodes)| CommandNode::Literal {
+            literals: literals.clone(),
+            ignore_case,
+            child_nodes,
+        })
+        .collect()
+}
@@ -1,35 +1,54 @@
 mod action;
 mod authority;
+mod cooldown;
+mod help;
 mod init;
 mod parse;
+mod template;
 mod tree;
 
 pub use self::action::CommandAction;
 pub use self::authority::AuthorityLevel;
+pub use self::cooldown::CooldownScope;
 pub use self::parse::{ArgsError, CommandParseError};
 pub use self::tree::CommandTree;
 
-use crate::config::SimpleCommands;
+use crate::config::{CommandSettings, SimpleCommands};
 
 use super::action::Action;
 use super::*;
 
 use std::collections::BTreeMap;
 
-use minmands::{command, CommandBuilder, CommandNode, ParseError};
+use minmands::{
+    Arguments, Command, CommandBuilder, CommandNode, Completion, Dispatcher, ParseError, Subtrees,
+};
 
-// Note: Make sure to add new field to [Commands::iter_mut] method.
+// Note: Make sure to add new field to [Commands::iter] and [Commands::iter_mut] methods.
 pub struct Commands {
     /// Specified in the config and updated on config reload.
     configured: Vec<CommandTree>,
     /// Hardcoded commands.
     hardcoded: Vec<CommandTree>,
+    /// Finds the commands that may match a message.
+    /// Indices refer to the order of [Commands::iter], rebuilt on reload.
+    dispatcher: Dispatcher,
+    /// Minimal time in seconds between typo suggestions in chat.
+    suggestion_cooldown: f64,
+    /// Time until the next typo suggestion can be sent.
+    suggestion_timer: f64,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct CommandCall<'a> {
     pub message: &'a str,
     pub authority: AuthorityLevel,
+    /// Display name of the caller.
+    pub user: &'a str,
+    /// Twitch id of the caller, which stays the same when they change their name.
+    pub user_id: &'a str,
+    /// Channel the command was called in.
+    pub channel: &'a str,
 }
 
 impl Commands {
@@ -38,6 +57,48 @@ impl Commands {
         for command in self.iter_mut() {
             command.update(delta_time);
         }
+        self.suggestion_timer = (self.suggestion_timer - delta_time).max(0.0);
+    }
+
+    /// Whether a typo suggestion can be sent now. If so, starts the cooldown.
+    fn take_suggestion(&mut self) -> bool {
+        if self.suggestion_timer > 0.0 {
+            return false;
+        }
+        self.suggestion_timer = self.suggestion_cooldown;
+        true
+    }
+
+    /// Finds possible continuations of the last word in the input
+    /// across all commands.
+    pub fn complete(&self, input: &str) -> Completion {
+        let mut completion = Completion::new(input);
+        for command in self.iter() {
+            completion.merge(command.complete(input));
+        }
+        completion
+    }
+
+    /// Name of the command closest to the first word of the message,
+    /// among the ones the caller can use, if the word looks like a typo of it.
+    fn suggest(&self, message: &str, authority: AuthorityLevel) -> Option<String> {
+        let commands: Vec<&CommandTree> = self.iter().collect();
+        self.dispatcher
+            .suggest(message, |index| commands[index].is_authorized(authority))
+            .map(str::to_owned)
+    }
+
+    /// Commands that may match the message.
+    fn candidates_mut(&mut self, message: &str) -> impl Iterator<Item = &mut CommandTree> {
+        let candidates = self.dispatcher.candidates(message);
+        self.iter_mut()
+            .enumerate()
+            .filter(move |(i, _)| candidates.binary_search(i).is_ok())
+            .map(|(_, command)| command)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &CommandTree> {
+        iter_tools::chain![&self.configured, &self.hardcoded]
     }
 
     fn iter_mut(&mut self) -> impl Iterator<Item = &mut CommandTree> {
@@ -49,25 +110,80 @@ impl Model {
     pub fn handle_command_call(&mut self, call: CommandCall) -> Vec<AppAction> {
         // Parse commands
         let mut actions = Vec::new();
-        for command in self.commands.iter_mut() {
+        // The most helpful parse error among the commands the caller can use
+        let mut parse_error: Option<ParseError> = None;
+        // Commands that parsed but could not be called
+        let mut rejected = Vec::new();
+        for command in self.commands.candidates_mut(call.message) {
             // Cooldown is checked and updated inside `parse`
-            match command.parse(call) {
+            match command.parse(call, &self.counters) {
                 Ok(action) => actions.push(action),
-                Err(parse::CommandParseError::Parse(_)) => continue, // Did not parse
+                Err(parse::CommandParseError::Parse(err)) => {
+                    // Did not parse, do not suggest commands the caller cannot use
+                    if command.is_authorized(call.authority)
+                        && parse_error
+                            .as_ref()
+                            .is_none_or(|best| err.is_better_than(best, call.message))
+                    {
+                        parse_error = Some(err);
+                    }
+                }
                 Err(parse::CommandParseError::Args(err)) => {
                     // Parsed, but action could not be formed
-                    log::debug!(
+                    log::warn!(
                         "Action could not be formed: {}\n  for call: {:?}\n  for command: {:?}",
                         err,
                         call,
                         command
                     );
-                    continue;
                 }
-                Err(parse::CommandParseError::Call(_)) => {
+                Err(parse::CommandParseError::Call(err)) => {
                     // Invalid call
-                    continue;
+                    log::info!(
+                        "{} called by {} {}",
+                        command.names().first().map_or("Command", String::as_str),
+                        call.user,
+                        err
+                    );
+                    rejected.push((command, err));
+                }
+            }
+        }
+
+        if actions.is_empty() {
+            // Explain why the command did not fire
+            let feedback = rejected
+                .into_iter()
+                .find_map(|(command, err)| command.feedback(&err, call));
+            actions.extend(feedback.map(Action::Say));
+        }
+
+        if actions.is_empty() && call.message.starts_with('!') {
+            if let Some(err) = &parse_error {
+                if err.offset > 0 {
+                    log::debug!("Command {:?} failed to parse: {}", call.message, err);
+                }
+            }
+            let suggestion = match parse_error {
+                Some(err) if err.offset > 0 || err.suggestion.is_some() => {
+                    err.suggestion.map(|suggestion| (err.offset, suggestion))
                 }
+                // No command starts with the first word, so it may be a typo of a name
+                _ => self
+                    .commands
+                    .suggest(call.message, call.authority)
+                    .map(|suggestion| (0, suggestion)),
+            };
+            let suggestion = suggestion.filter(|_| self.commands.take_suggestion());
+            if let Some((offset, suggestion)) = suggestion {
+                let found = call.message[offset..]
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default();
+                let kind = if offset == 0 { "command" } else { "subcommand" };
+                actions.push(Action::Say(format!(
+                    "Unknown {kind} {found:?}, did you mean {suggestion:?}?"
+                )));
             }
         }
 
@@ -79,3 +195,136 @@ impl Model {
         app_actions
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CommandCall;
+    use crate::{app::AppAction, config::Config, model::*};
+
+    fn model(config: &Config) -> Model {
+        Model::new(
+            config,
+            "streamer".to_owned(),
+            Counters::default(),
+            Quotes::default(),
+            ViewerQueue::default(),
+        )
+    }
+
+    /// Messages sent to chat in response to the call.
+    fn call(model: &mut Model, message: &str, authority: AuthorityLevel) -> Vec<String> {
+        let call = CommandCall {
+            message,
+            authority,
+            user: "someone",
+            user_id: "1",
+            channel: "streamer",
+        };
+        model
+            .handle_command_call(call)
+            .into_iter()
+            .filter_map(|action| match action {
+                AppAction::Say { message } => Some(message),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut model = model(&Config::default());
+
+        // Not the first command that is tried
+        assert_eq!(
+            call(&mut model, "!leav", AuthorityLevel::Viewer),
+            ["Unknown command \"!leav\", did you mean \"!leave\"?"]
+        );
+        // Rate limited
+        assert!(call(&mut model, "!leav", AuthorityLevel::Viewer).is_empty());
+        model.update(10.0).unwrap();
+        // Only commands the caller can use are suggested
+        assert!(call(&mut model, "!relaod", AuthorityLevel::Viewer).is_empty());
+        assert_eq!(
+            call(&mut model, "!relaod", AuthorityLevel::Broadcaster),
+            ["Unknown command \"!relaod\", did you mean \"!reload\"?"]
+        );
+        model.update(10.0).unwrap();
+        assert_eq!(
+            call(&mut model, "!poll stat", AuthorityLevel::Moderator),
+            ["Unknown subcommand \"stat\", did you mean \"start\"?"]
+        );
+    }
+
+    #[test]
+    fn test_reload_settings() {
+        let mut config = Config::default();
+        let mut model = model(&config);
+        assert_eq!(call(&mut model, "!hello", AuthorityLevel::Viewer).len(), 1);
+        assert!(call(&mut model, "!hello", AuthorityLevel::Viewer).is_empty());
+
+        // Hardcoded commands follow the reloaded defaults
+        config.commands.cooldown_bypass = Some(AuthorityLevel::Viewer);
+        model.reload(&config);
+        assert_eq!(call(&mut model, "!hello", AuthorityLevel::Viewer).len(), 1);
+
+        config.commands.feedback = false;
+        model.reload(&config);
+        assert!(call(&mut model, "!reload", AuthorityLevel::Viewer).is_empty());
+        config.commands.feedback = true;
+        model.reload(&config);
+        assert_eq!(call(&mut model, "!reload", AuthorityLevel::Viewer).len(), 1);
+    }
+
+    #[test]
+    fn test_help() {
+        let model = model(&Config::default());
+        for name in ["!QUOTE", "Hello"] {
+            let usage = model.commands.usage(name, AuthorityLevel::Viewer);
+            assert_eq!(
+                usage,
+                model
+                    .commands
+                    .usage(&name.to_lowercase(), AuthorityLevel::Viewer)
+            );
+            assert!(usage.starts_with("Usage: "), "{usage}");
+        }
+        assert_eq!(
+            model.commands.usage("reload", AuthorityLevel::Viewer),
+            "Unknown command: !reload"
+        );
+    }
+
+    #[test]
+    fn test_branch_authority() {
+        let mut config = Config::default();
+        config.commands.feedback = true;
+        let mut model = model(&config);
+        assert_eq!(
+            call(&mut model, "!queue", AuthorityLevel::Viewer),
+            ["Queue (closed, 0 viewers)"]
+        );
+        assert_eq!(
+            call(&mut model, "!queue clear", AuthorityLevel::Viewer),
+            ["@someone, !queue requires moderator authority"]
+        );
+        // Not blocked by the cooldown of showing the queue
+        assert_eq!(
+            call(&mut model, "!queue clear", AuthorityLevel::Moderator),
+            ["The queue has been cleared"]
+        );
+        assert_eq!(
+            model.commands.usage("queue", AuthorityLevel::Viewer),
+            "Usage: !queue"
+        );
+    }
+
+    #[test]
+    fn test_hardcoded_roots() {
+        // Every branch can be called and split by its settings
+        for root in Commands::hardcoded_roots() {
+            for branch in root.children() {
+                assert!(!branch.final_values().is_empty(), "{:?}", root.usage());
+            }
+        }
+    }
+}
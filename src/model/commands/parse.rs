@@ -1,4 +1,4 @@
-use super::*;
+use super::{template::TemplateError, *};
 
 #[derive(Debug, Clone)]
 pub enum CommandParseError {
@@ -9,14 +9,18 @@ pub enum CommandParseError {
 
 #[derive(Debug, Clone)]
 pub enum ArgsError {
-    TooMany,
-    NotEnough,
+    /// Parsed arguments do not fit the fields of the command.
+    Mismatch,
+    /// The response template could not be rendered.
+    Template(TemplateError),
 }
 
 #[derive(Debug, Clone)]
 pub enum CallError {
-    OnCooldown,
-    Unauthorized,
+    /// Seconds left until the cooldown expires.
+    OnCooldown(f64),
+    /// The authority required to call the command.
+    Unauthorized(AuthorityLevel),
 }
 
 impl From<ParseError> for CommandParseError {
@@ -31,6 +35,12 @@ impl From<ArgsError> for CommandParseError {
     }
 }
 
+impl From<TemplateError> for ArgsError {
+    fn from(v: TemplateError) -> Self {
+        Self::Template(v)
+    }
+}
+
 impl From<CallError> for CommandParseError {
     fn from(v: CallError) -> Self {
         Self::Call(v)
@@ -42,8 +52,8 @@ impl std::error::Error for ArgsError {}
 impl std::fmt::Display for ArgsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ArgsError::TooMany => write!(f, "Too many arguments"),
-            ArgsError::NotEnough => write!(f, "Not enough arguments"),
+            ArgsError::Mismatch => write!(f, "Arguments do not match the command"),
+            ArgsError::Template(err) => write!(f, "Failed to render the response: {err}"),
         }
     }
 }
@@ -53,8 +63,8 @@ impl std::error::Error for CallError {}
 impl std::fmt::Display for CallError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CallError::OnCooldown => write!(f, "Command is on cooldown"),
-            CallError::Unauthorized => write!(f, "Unauthorized"),
+            CallError::OnCooldown(time) => write!(f, "is on cooldown for {}s", time.ceil()),
+            CallError::Unauthorized(level) => write!(f, "requires {level} authority"),
         }
     }
 }
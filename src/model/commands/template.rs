@@ -0,0 +1,311 @@
+use minmands::ArgumentValue;
+use rand::Rng;
+
+use super::*;
+
+/// Information available to a response template.
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateContext<'a> {
+    pub call: CommandCall<'a>,
+    /// Arguments parsed by the command tree, including the defaults of omitted ones.
+    pub arguments: &'a Arguments,
+    /// How many times the command has been used since the bot started, including this call.
+    pub count: u64,
+    pub counters: &'a Counters,
+}
+
+/// A placeholder in a response template, written as `${name}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variable<'a> {
+    /// `${user}`: display name of the caller, or their login if they have none.
+    User,
+    /// `${channel}`: channel the command was called in.
+    Channel,
+    /// `${args}`: the message after the command name.
+    Args,
+    /// `${1}`, `${2}`, ...: an argument parsed by the command tree, starting from 1.
+    /// Commands without arguments use the words of the message after the command name instead.
+    Arg(usize),
+    /// `${random 1 100}`: a random whole number in the inclusive range.
+    Random(i64, i64),
+    /// `${time}`: current time in UTC.
+    Time,
+    /// `${count}`: how many times the command has been used.
+    /// Kept when the config is reloaded, but starts from 0 when the bot restarts.
+    Count,
+    /// `${counter deaths}`: current value of the named counter.
+    Counter(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part<'a> {
+    Text(&'a str),
+    Variable(Variable<'a>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `${` at the byte offset is never closed.
+    Unclosed(usize),
+    UnknownVariable(String),
+    InvalidRandom(String),
+    InvalidCounter(String),
+    /// The command has fewer arguments than the template refers to.
+    MissingArgument(usize),
+}
+
+impl std::error::Error for TemplateError {}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Unclosed(offset) => {
+                write!(f, "Placeholder at {offset} is missing a closing }}")
+            }
+            TemplateError::UnknownVariable(name) => write!(f, "Unknown placeholder ${{{name}}}"),
+            TemplateError::InvalidRandom(source) => write!(
+                f,
+                "Invalid placeholder ${{{source}}}, expected ${{random <min> <max>}}"
+            ),
+            TemplateError::InvalidCounter(source) => write!(
+                f,
+                "Invalid placeholder ${{{source}}}, expected ${{counter <name>}}"
+            ),
+            TemplateError::MissingArgument(index) => {
+                write!(f, "Missing argument ${{{index}}}")
+            }
+        }
+    }
+}
+
+/// Checks that the template is well-formed.
+pub fn validate(template: &str) -> Result<(), TemplateError> {
+    parse(template).map(|_| ())
+}
+
+/// Whether the template refers to the message after the command name.
+pub fn uses_arguments(template: &str) -> bool {
+    parse(template).is_ok_and(|parts| {
+        parts.iter().any(|part| {
+            matches!(
+                part,
+                Part::Variable(Variable::Args) | Part::Variable(Variable::Arg(_))
+            )
+        })
+    })
+}
+
+/// Substitutes the placeholders in the template.
+pub fn render(template: &str, context: &TemplateContext) -> Result<String, TemplateError> {
+    let args = context
+        .call
+        .message
+        .trim()
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, args)| args.trim());
+
+    let mut result = String::new();
+    for part in parse(template)? {
+        match part {
+            Part::Text(text) => result.push_str(text),
+            Part::Variable(variable) => match variable {
+                Variable::User => result.push_str(context.call.user),
+                Variable::Channel => result.push_str(context.call.channel),
+                Variable::Args => result.push_str(args),
+                Variable::Arg(index) => {
+                    let missing = || TemplateError::MissingArgument(index);
+                    if context.arguments.is_empty() {
+                        let arg = args.split_whitespace().nth(index - 1).ok_or_else(missing)?;
+                        result.push_str(arg);
+                    } else {
+                        let arg = context
+                            .arguments
+                            .get_index::<ArgumentValue>(index - 1)
+                            .ok_or_else(missing)?;
+                        // Omitted optional arguments without a default are left empty
+                        result.push_str(&arg.to_string());
+                    }
+                }
+                Variable::Random(min, max) => {
+                    let value = rand::thread_rng().gen_range(min..=max);
+                    result.push_str(&value.to_string());
+                }
+                Variable::Time => result.push_str(&current_time()),
+                Variable::Count => result.push_str(&context.count.to_string()),
+                Variable::Counter(name) => result.push_str(&context.counters.get(name).to_string()),
+            },
+        }
+    }
+    Ok(result)
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, TemplateError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        if start > 0 {
+            parts.push(Part::Text(&rest[..start]));
+        }
+        let offset = template.len() - rest.len() + start;
+        let source = &rest[start + 2..];
+        let end = source.find('}').ok_or(TemplateError::Unclosed(offset))?;
+        parts.push(Part::Variable(parse_variable(source[..end].trim())?));
+        rest = &source[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    Ok(parts)
+}
+
+fn parse_variable(source: &str) -> Result<Variable<'_>, TemplateError> {
+    let mut words = source.split_whitespace();
+    let variable = match words.next().unwrap_or_default() {
+        "user" => Variable::User,
+        "channel" => Variable::Channel,
+        "args" => Variable::Args,
+        "time" => Variable::Time,
+        "count" => Variable::Count,
+        "random" => {
+            let invalid = || TemplateError::InvalidRandom(source.to_owned());
+            let mut bound = || -> Result<i64, TemplateError> {
+                words
+                    .next()
+                    .and_then(|word| word.parse().ok())
+                    .ok_or_else(invalid)
+            };
+            let (min, max) = (bound()?, bound()?);
+            if min > max {
+                return Err(invalid());
+            }
+            Variable::Random(min, max)
+        }
+        "counter" => Variable::Counter(
+            words
+                .next()
+                .ok_or_else(|| TemplateError::InvalidCounter(source.to_owned()))?,
+        ),
+        name => match name.parse::<usize>() {
+            Ok(index) if index > 0 => Variable::Arg(index),
+            _ => return Err(TemplateError::UnknownVariable(source.to_owned())),
+        },
+    };
+    match words.next() {
+        Some(_) => Err(match variable {
+            Variable::Random(..) => TemplateError::InvalidRandom(source.to_owned()),
+            Variable::Counter(_) => TemplateError::InvalidCounter(source.to_owned()),
+            _ => TemplateError::UnknownVariable(source.to_owned()),
+        }),
+        None => Ok(variable),
+    }
+}
+
+/// Current time of day in UTC, e.g. `14:05 UTC`.
+fn current_time() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    let minutes = secs / 60 % 60;
+    let hours = secs / 60 / 60 % 24;
+    format!("{hours:02}:{minutes:02} UTC")
+}
+
+#[cfg(test)]
+mod tests {
+    use minmands::ArgumentType;
+
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut counters = Counters::default();
+        counters.add("Deaths", 2);
+        let arguments = Arguments::new();
+        let context = TemplateContext {
+            call: CommandCall {
+                message: "!so  nertsal  hi",
+                authority: AuthorityLevel::Viewer,
+                user: "viewer",
+                user_id: "1",
+                channel: "streamer",
+            },
+            arguments: &arguments,
+            count: 3,
+            counters: &counters,
+        };
+        let render = |template| render(template, &context);
+
+        assert_eq!(
+            render("Hi, ${user}! Welcome to ${channel} #${count}").as_deref(),
+            Ok("Hi, viewer! Welcome to streamer #3")
+        );
+        assert_eq!(
+            render("${args}|${ 1 }|${2}").as_deref(),
+            Ok("nertsal  hi|nertsal|hi")
+        );
+        assert_eq!(render("$5 and ${random 7 7}").as_deref(), Ok("$5 and 7"));
+        assert_eq!(
+            render("${counter deaths} deaths, ${counter wins} wins").as_deref(),
+            Ok("2 deaths, 0 wins")
+        );
+        assert!(matches!(
+            render("${counter}"),
+            Err(TemplateError::InvalidCounter(_))
+        ));
+        assert_eq!(render("${3}"), Err(TemplateError::MissingArgument(3)));
+        assert_eq!(render("oops ${user"), Err(TemplateError::Unclosed(5)));
+        assert!(matches!(
+            render("${name}"),
+            Err(TemplateError::UnknownVariable(_))
+        ));
+        assert!(matches!(
+            render("${random 5 1}"),
+            Err(TemplateError::InvalidRandom(_))
+        ));
+        assert!(matches!(
+            render("${random 1}"),
+            Err(TemplateError::InvalidRandom(_))
+        ));
+
+        assert!(uses_arguments("Check out ${1}!"));
+        assert!(!uses_arguments("Hi, ${user}"));
+    }
+
+    #[test]
+    fn test_render_arguments() {
+        let node = CommandBuilder::new()
+            .literal(["!roll"])
+            .optional_or(
+                ArgumentType::Integer {
+                    min: Some(1),
+                    max: None,
+                },
+                6,
+            )
+            .optional(ArgumentType::Word)
+            .finalize(true, ());
+        let message = "!roll";
+        let parsed = node.parse(message).unwrap();
+        let counters = Counters::default();
+        let context = TemplateContext {
+            call: CommandCall {
+                message,
+                authority: AuthorityLevel::Viewer,
+                user: "viewer",
+                user_id: "1",
+                channel: "streamer",
+            },
+            arguments: &parsed.arguments,
+            count: 1,
+            counters: &counters,
+        };
+        assert_eq!(
+            render("Rolled d${1}${2}", &context).as_deref(),
+            Ok("Rolled d6")
+        );
+        assert_eq!(
+            render("${3}", &context),
+            Err(TemplateError::MissingArgument(3))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::{parse::CallError, *};
+use super::{cooldown::CooldownKey, parse::CallError, template::TemplateContext, *};
 
 #[derive(Debug, Clone)]
 pub struct CommandTree {
@@ -6,8 +6,19 @@ pub struct CommandTree {
     authority_level: AuthorityLevel,
     /// Command cooldown in seconds.
     cooldown: f64,
-    /// Time until cooldown expires for individual argument variants.
-    cooldown_timers: BTreeMap<Vec<String>, f64>,
+    cooldown_scope: CooldownScope,
+    /// Callers with this authority or higher are not affected by the cooldown.
+    cooldown_bypass: Option<AuthorityLevel>,
+    /// Time until cooldown expires for the calls that share it (see [CooldownScope]).
+    cooldown_timers: BTreeMap<CooldownKey, f64>,
+    /// Whether to tell the caller in chat why the command did not fire.
+    feedback: bool,
+    /// Minimal time in seconds between feedback messages.
+    feedback_cooldown: f64,
+    /// Time until the next feedback message can be sent.
+    feedback_timer: f64,
+    /// How many times the command has been used since the bot started.
+    uses: u64,
 }
 
 impl CommandTree {
@@ -17,6 +28,18 @@ impl CommandTree {
             *time -= delta_time;
         }
         self.cooldown_timers.retain(|_, time| *time > 0.0);
+        self.feedback_timer = (self.feedback_timer - delta_time).max(0.0);
+    }
+
+    /// Message explaining to the caller why the command did not fire,
+    /// if feedback is enabled and was not sent too recently.
+    pub fn feedback(&mut self, error: &CallError, call: CommandCall) -> Option<String> {
+        if !self.feedback || self.feedback_timer > 0.0 {
+            return None;
+        }
+        self.feedback_timer = self.feedback_cooldown;
+        let name = self.names().first().map_or("The command", String::as_str);
+        Some(format!("@{}, {} {}", call.user, name, error))
     }
 }
 
@@ -26,7 +49,13 @@ impl CommandTree {
             root,
             authority_level: AuthorityLevel::Viewer,
             cooldown: 0.0,
+            cooldown_scope: CooldownScope::default(),
+            cooldown_bypass: None,
             cooldown_timers: BTreeMap::new(),
+            feedback: false,
+            feedback_cooldown: 0.0,
+            feedback_timer: 0.0,
+            uses: 0,
         }
     }
 
@@ -35,31 +64,131 @@ impl CommandTree {
         self
     }
 
+    pub fn with_cooldown_scope(mut self, scope: CooldownScope) -> Self {
+        self.cooldown_scope = scope;
+        self
+    }
+
+    /// Let callers with the given authority or higher ignore the cooldown.
+    pub fn with_cooldown_bypass(mut self, level: Option<AuthorityLevel>) -> Self {
+        self.cooldown_bypass = level;
+        self
+    }
+
+    /// Reply in chat when the command is on cooldown or the caller is unauthorized,
+    /// at most once per `cooldown` seconds.
+    pub fn with_feedback(mut self, feedback: bool, cooldown: f64) -> Self {
+        self.feedback = feedback;
+        self.feedback_cooldown = cooldown;
+        self
+    }
+
     pub fn with_authority(mut self, level: AuthorityLevel) -> Self {
         self.authority_level = level;
         self
     }
 
-    pub fn parse(&mut self, call: CommandCall) -> Result<Action, CommandParseError> {
+    /// Applies the settings from the config, using the defaults for the unset ones.
+    pub fn with_settings(self, settings: &CommandSettings, config: &SimpleCommands) -> Self {
+        self.with_authority(settings.authority)
+            .with_cooldown(settings.cooldown.unwrap_or(config.cooldown))
+            .with_cooldown_scope(settings.cooldown_scope.unwrap_or(config.cooldown_scope))
+            .with_cooldown_bypass(settings.cooldown_bypass.or(config.cooldown_bypass))
+            .with_feedback(
+                settings.feedback.unwrap_or(config.feedback),
+                settings
+                    .feedback_cooldown
+                    .unwrap_or(config.feedback_cooldown),
+            )
+    }
+
+    /// Authority level required to use the command.
+    pub fn authority(&self) -> AuthorityLevel {
+        self.authority_level
+    }
+
+    /// Command cooldown in seconds.
+    pub fn cooldown(&self) -> f64 {
+        self.cooldown
+    }
+
+    /// How many times the command has been used since the bot started.
+    pub fn uses(&self) -> u64 {
+        self.uses
+    }
+
+    /// Continues counting the uses from the given number, e.g. after a reload.
+    pub fn with_uses(mut self, uses: u64) -> Self {
+        self.uses = uses;
+        self
+    }
+
+    /// Whether a caller with the given authority is allowed to use the command.
+    pub fn is_authorized(&self, authority: AuthorityLevel) -> bool {
+        authority >= self.authority_level
+    }
+
+    pub fn root(&self) -> &CommandNode<CommandAction> {
+        &self.root
+    }
+
+    /// Literals that the command starts with, e.g. `!hello`.
+    pub fn names(&self) -> &[String] {
+        match &self.root {
+            CommandNode::Literal { literals, .. } => literals,
+            _ => &[],
+        }
+    }
+
+    /// Usage string for every variant of the command.
+    pub fn usage(&self) -> Vec<String> {
+        self.root.usage()
+    }
+
+    /// Finds possible continuations of the last word in the input.
+    pub fn complete(&self, input: &str) -> Completion {
+        self.root.complete(input)
+    }
+
+    /// `counters` are available to the response template.
+    pub fn parse(
+        &mut self,
+        call: CommandCall,
+        counters: &Counters,
+    ) -> Result<Action, CommandParseError> {
         // Parse
         let parsed = self.root.parse(call.message)?;
 
         // Check authority
-        if call.authority < self.authority_level {
-            return Err(CallError::Unauthorized.into());
+        if !self.is_authorized(call.authority) {
+            return Err(CallError::Unauthorized(self.authority_level).into());
         }
 
         // Check cooldown
-        if self.cooldown_timers.contains_key(&parsed.arguments) {
-            return Err(CallError::OnCooldown.into());
-        }
+        let bypass = self
+            .cooldown_bypass
+            .is_some_and(|level| call.authority >= level);
+        if !bypass {
+            let key = self.cooldown_scope.key(&call, &parsed.arguments);
+            if let Some(&time) = self.cooldown_timers.get(&key) {
+                return Err(CallError::OnCooldown(time).into());
+            }
 
-        // Set cooldown
-        self.cooldown_timers
-            .insert(parsed.arguments.clone(), self.cooldown);
+            // Set cooldown
+            if self.cooldown > 0.0 {
+                self.cooldown_timers.insert(key, self.cooldown);
+            }
+        }
+        self.uses += 1;
 
         // Get action
-        let action = parsed.value.into_action(parsed.arguments)?;
+        let context = TemplateContext {
+            call,
+            arguments: &parsed.arguments,
+            count: self.uses,
+            counters,
+        };
+        let action = parsed.value.into_action(context)?;
         Ok(action)
     }
 }
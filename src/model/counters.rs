@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Named counters, e.g. deaths or wins, that are saved between restarts.
+/// Names are case-insensitive, and a counter that was never set is 0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<String, i64>", into = "BTreeMap<String, i64>")]
+pub struct Counters {
+    values: BTreeMap<String, i64>,
+}
+
+impl From<BTreeMap<String, i64>> for Counters {
+    fn from(values: BTreeMap<String, i64>) -> Self {
+        // The file may have been edited by hand
+        let values = values
+            .into_iter()
+            .map(|(name, value)| (name.to_lowercase(), value))
+            .collect();
+        Self { values }
+    }
+}
+
+impl From<Counters> for BTreeMap<String, i64> {
+    fn from(counters: Counters) -> Self {
+        counters.values
+    }
+}
+
+impl Counters {
+    pub fn get(&self, name: &str) -> i64 {
+        self.values
+            .get(&name.to_lowercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Adds the amount to the counter, and returns the new value.
+    pub fn add(&mut self, name: &str, amount: i64) -> i64 {
+        let value = self.values.entry(name.to_lowercase()).or_default();
+        *value = value.saturating_add(amount);
+        *value
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) {
+        self.values.insert(name.to_lowercase(), value);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.values
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters() {
+        let mut counters = Counters::default();
+        assert_eq!(counters.get("deaths"), 0);
+        assert_eq!(counters.add("Deaths", 2), 2);
+        assert_eq!(counters.add("deaths", -1), 1);
+        assert_eq!(counters.get("DEATHS"), 1);
+
+        counters.set("wins", i64::MAX);
+        assert_eq!(counters.add("Wins", 1), i64::MAX);
+        counters.set("WINS", 3);
+        assert_eq!(counters.get("wins"), 3);
+        assert_eq!(
+            counters.iter().collect::<Vec<_>>(),
+            [("deaths", 1), ("wins", 3)]
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let mut counters: Counters = toml::from_str("Deaths = 5\nwins = 1").unwrap();
+        assert_eq!(counters.get("deaths"), 5);
+        assert_eq!(counters.add("deaths", 1), 6);
+        assert_eq!(counters.iter().count(), 2);
+        assert_eq!(
+            toml::to_string(&counters).unwrap(),
+            "deaths = 6\nwins = 1\n"
+        );
+    }
+}
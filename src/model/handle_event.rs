@@ -2,6 +2,8 @@ use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 
 use crate::client::TwitchMessage;
 
+use minmands::ArgumentType;
+
 use super::commands::{AuthorityLevel, CommandCall};
 use super::*;
 
@@ -23,11 +25,34 @@ impl Model {
                 }
 
                 // Check command
+                let authority = AuthorityLevel::from_badges(&message.badges);
                 let call = CommandCall {
                     message: &message.message_text,
-                    authority: AuthorityLevel::from_badges(&message.badges),
+                    authority,
+                    user: &message.sender.name,
+                    user_id: &message.sender.id,
+                    channel: &message.channel_login,
                 };
                 let actions = self.handle_command_call(call);
+                self.timers.chat_line();
+
+                // A bare number is a vote
+                if let Some(poll) = &mut self.poll {
+                    if let Ok(option) = message.message_text.trim().parse() {
+                        poll.vote(&message.sender.id, option);
+                    }
+                }
+
+                // Typing the keyword enters the raffle
+                if let Some(raffle) = &mut self.raffle {
+                    raffle.enter(
+                        &message.sender.id,
+                        &message.sender.name,
+                        authority,
+                        &message.message_text,
+                        &self.raffle_config,
+                    );
+                }
 
                 // Log
                 let msg = ChatMessage {
@@ -63,6 +88,21 @@ impl Model {
             }
         }
 
+        // Tab completion in the input box
+        if let ChatMode::Insert = self.chat.mode {
+            match event.code {
+                KeyCode::Tab => {
+                    self.complete_input(true);
+                    return vec![];
+                }
+                KeyCode::BackTab => {
+                    self.complete_input(false);
+                    return vec![];
+                }
+                _ => {}
+            }
+        }
+
         // TODO: focused window only
         let actions = self.chat.handle_key(event);
 
@@ -73,3 +113,40 @@ impl Model {
         app_actions
     }
 }
+
+impl Model {
+    /// Cycle through the completion candidates for the word before the cursor.
+    /// Candidates are command literals and, where a word is expected, chatter names.
+    fn complete_input(&mut self, forward: bool) {
+        let input = &mut self.chat.input;
+        if input.is_completing() {
+            input.cycle_completion(forward);
+            return;
+        }
+
+        let completion = self.commands.complete(input.before_cursor());
+        let mut candidates = completion.candidates;
+        if completion.expected.iter().any(|argument_type| {
+            matches!(
+                argument_type,
+                ArgumentType::Word | ArgumentType::Quoted | ArgumentType::User
+            )
+        }) {
+            let word = input.before_cursor()[completion.offset..].to_lowercase();
+            // Keep the mention prefix
+            let (prefix, word) = match word.strip_prefix('@') {
+                Some(word) => ("@", word),
+                None => ("", word.as_str()),
+            };
+            let mut names: Vec<&String> = self
+                .chat
+                .chatters
+                .keys()
+                .filter(|name| name.to_lowercase().starts_with(word))
+                .collect();
+            names.sort();
+            candidates.extend(names.into_iter().map(|name| format!("{prefix}{name}")));
+        }
+        input.start_completion(completion.offset, candidates);
+    }
+}
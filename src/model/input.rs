@@ -5,6 +5,17 @@ pub struct InputBox {
     pub content: String,
     /// Cursor position.
     pub cursor: usize,
+    /// Candidates that are being cycled through with Tab.
+    completion: Option<InputCompletion>,
+}
+
+#[derive(Debug, Clone)]
+struct InputCompletion {
+    /// Byte offset where the completed word starts.
+    offset: usize,
+    candidates: Vec<String>,
+    /// Index of the currently inserted candidate.
+    current: usize,
 }
 
 impl InputBox {
@@ -14,9 +25,56 @@ impl InputBox {
 
     pub fn take(&mut self) -> String {
         self.cursor = 0;
+        self.completion = None;
         std::mem::take(&mut self.content)
     }
 
+    /// Text before the cursor.
+    pub fn before_cursor(&self) -> &str {
+        &self.content[..self.cursor.min(self.content.len())]
+    }
+
+    /// Whether Tab is currently cycling through completion candidates.
+    pub fn is_completing(&self) -> bool {
+        self.completion.is_some()
+    }
+
+    /// Starts cycling through the candidates by replacing the text
+    /// from `offset` to the cursor with the first candidate.
+    pub fn start_completion(&mut self, offset: usize, candidates: Vec<String>) {
+        if candidates.is_empty() {
+            return;
+        }
+        self.completion = Some(InputCompletion {
+            offset,
+            candidates,
+            current: 0,
+        });
+        self.apply_completion();
+    }
+
+    /// Replaces the inserted candidate with the next (or previous) one.
+    pub fn cycle_completion(&mut self, forward: bool) {
+        if let Some(completion) = &mut self.completion {
+            let len = completion.candidates.len();
+            completion.current = if forward {
+                (completion.current + 1) % len
+            } else {
+                (completion.current + len - 1) % len
+            };
+            self.apply_completion();
+        }
+    }
+
+    fn apply_completion(&mut self) {
+        if let Some(completion) = &self.completion {
+            let candidate = &completion.candidates[completion.current];
+            let offset = completion.offset.min(self.cursor);
+            self.content.replace_range(offset..self.cursor, candidate);
+            self.cursor = offset + candidate.len();
+        }
+    }
+
     fn check_cursor(&mut self) {
         if self.cursor > self.content.len() {
             self.cursor = self.content.len();
@@ -36,6 +94,7 @@ impl InputBox {
     }
 
     pub fn handle_key(&mut self, event: KeyEvent) {
+        self.completion = None;
         match event.code {
             KeyCode::Char(c) => {
                 self.insert(self.cursor, c);
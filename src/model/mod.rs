@@ -1,32 +1,73 @@
 mod action;
 mod chat;
 mod commands;
+mod counters;
 mod handle_event;
 mod input;
+mod poll;
+mod queue;
+mod quotes;
+mod raffle;
+mod timers;
 
 use std::collections::HashMap;
 
 use tui::style::Color;
 
 use crate::app::AppAction;
-use crate::config::Config;
+use crate::config::{Config, QueueConfig, RaffleConfig};
 
 pub use self::chat::*;
 use self::commands::Commands;
+pub use self::commands::{AuthorityLevel, CooldownScope};
+pub use self::counters::Counters;
 pub use self::input::*;
+pub use self::poll::Poll;
+pub use self::queue::{JoinError, ViewerQueue};
+pub use self::quotes::{Quote, QuoteQuery, Quotes};
+pub use self::raffle::Raffle;
+pub use self::timers::Timers;
 
 pub struct Model {
     /// Set to false to shutdown gracefully.
     pub running: bool,
+    /// Name of the channel the bot is connected to.
+    pub channel_login: String,
     pub commands: Commands,
+    pub counters: Counters,
+    pub quotes: Quotes,
+    pub timers: Timers,
+    /// The poll that chat is currently voting in.
+    pub poll: Option<Poll>,
+    pub raffle: Option<Raffle>,
+    raffle_config: RaffleConfig,
+    /// Viewers waiting for their turn.
+    pub queue: ViewerQueue,
+    queue_config: QueueConfig,
     pub chat: Chat,
 }
 
 impl Model {
-    pub fn new(config: &Config) -> Self {
+    /// `counters`, `quotes` and `queue` are restored from the previous run.
+    pub fn new(
+        config: &Config,
+        channel_login: String,
+        counters: Counters,
+        quotes: Quotes,
+        queue: ViewerQueue,
+    ) -> Self {
         Self {
             running: true,
+            channel_login,
             commands: Commands::init(&config.commands),
+            counters,
+            quotes,
+            timers: Timers::new(&config.timers),
+            poll: None,
+            raffle: None,
+            raffle_config: config.raffle.clone(),
+            queue,
+            queue_config: config.queue.clone(),
             chat: Chat::new(),
         }
     }
@@ -34,10 +75,25 @@ impl Model {
     /// Reload configuration.
     pub fn reload(&mut self, config: &Config) {
         self.commands.reload(&config.commands);
+        self.timers.reload(&config.timers);
+        self.raffle_config = config.raffle.clone();
+        self.queue_config = config.queue.clone();
     }
 
     pub fn update(&mut self, delta_time: f64) -> color_eyre::Result<Vec<AppAction>> {
         self.commands.update(delta_time);
-        Ok(vec![])
+        let mut messages = self.timers.update(delta_time);
+        if self
+            .poll
+            .as_mut()
+            .is_some_and(|poll| poll.update(delta_time))
+        {
+            messages.extend(self.poll.take().map(|poll| poll.summary()));
+        }
+        let actions = messages
+            .into_iter()
+            .map(|message| AppAction::Say { message })
+            .collect();
+        Ok(actions)
     }
 }
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+/// A poll that chat votes in by the option's number.
+#[derive(Debug, Clone)]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+    /// Index of the option that each user voted for, by user id.
+    votes: BTreeMap<String, usize>,
+    /// Time in seconds until the poll ends.
+    pub time_left: f64,
+}
+
+impl Poll {
+    pub fn new(question: String, options: Vec<String>, duration: f64) -> Self {
+        Self {
+            question,
+            options,
+            votes: BTreeMap::new(),
+            time_left: duration,
+        }
+    }
+
+    /// Parses the options separated by `|`, where the last one may end with
+    /// the duration of the poll, e.g. `yes | no 2m`.
+    /// The duration needs a unit, so that options like `Part 1` are kept whole.
+    /// Returns `None` if there are less than 2 options.
+    pub fn parse_options(source: &str) -> Option<(Vec<String>, Option<f64>)> {
+        let mut options: Vec<String> = source
+            .split('|')
+            .map(|option| option.trim().to_owned())
+            .collect();
+        let mut duration = None;
+        if let Some(last) = options.last_mut() {
+            if let Some((option, time)) = last.rsplit_once(char::is_whitespace) {
+                let time = time
+                    .ends_with(|c: char| c.is_ascii_alphabetic())
+                    .then(|| minmands::parse_duration(time))
+                    .flatten()
+                    .filter(|time| !time.is_zero());
+                if let Some(time) = time {
+                    duration = Some(time.as_secs_f64());
+                    *last = option.trim_end().to_owned();
+                }
+            }
+        }
+        options.retain(|option| !option.is_empty());
+        (options.len() >= 2).then_some((options, duration))
+    }
+
+    /// Counts the vote for the option with the given number, starting from 1.
+    /// Returns `false` if there is no such option or the user has already voted.
+    pub fn vote(&mut self, user_id: &str, option: usize) -> bool {
+        if option == 0 || option > self.options.len() {
+            return false;
+        }
+        if self.votes.contains_key(user_id) {
+            return false;
+        }
+        self.votes.insert(user_id.to_owned(), option - 1);
+        true
+    }
+
+    pub fn total_votes(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Options with the number of votes for each.
+    pub fn results(&self) -> Vec<(&str, usize)> {
+        let mut counts = vec![0; self.options.len()];
+        for &option in self.votes.values() {
+            counts[option] += 1;
+        }
+        self.options
+            .iter()
+            .map(String::as_str)
+            .zip(counts)
+            .collect()
+    }
+
+    /// Counts down the time, and returns `true` when the poll is over.
+    pub fn update(&mut self, delta_time: f64) -> bool {
+        self.time_left -= delta_time;
+        self.time_left <= 0.0
+    }
+
+    /// Message announcing the poll and how to vote.
+    pub fn announcement(&self) -> String {
+        let options: Vec<String> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| format!("{}) {}", index + 1, option))
+            .collect();
+        format!(
+            "Poll: {} {} | Vote with !vote <number> or just the number, {}s left",
+            self.question,
+            options.join(" "),
+            self.time_left.ceil()
+        )
+    }
+
+    /// Message with the final results.
+    pub fn summary(&self) -> String {
+        let results = self.results();
+        let total = self.total_votes().max(1);
+        let options: Vec<String> = results
+            .iter()
+            .map(|(option, votes)| format!("{} - {} ({}%)", option, votes, votes * 100 / total))
+            .collect();
+        let max = results.iter().map(|&(_, votes)| votes).max().unwrap_or(0);
+        let winners: Vec<&str> = results
+            .iter()
+            .filter(|&&(_, votes)| max > 0 && votes == max)
+            .map(|&(option, _)| option)
+            .collect();
+        let winner = match winners.as_slice() {
+            [] => "no votes".to_owned(),
+            [winner] => format!("{winner} wins"),
+            winners => format!("tie between {}", winners.join(" and ")),
+        };
+        format!(
+            "Poll ended: {} | {} | {}",
+            self.question,
+            options.join(", "),
+            winner
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twitch_irc::message::IRCMessage;
+
+    use crate::{app::AppAction, client::TwitchMessage, config::Config, model::*};
+
+    fn privmsg(name: &str, badges: &str, text: &str) -> TwitchMessage {
+        let login = name.to_lowercase();
+        let raw = format!(
+            "@badge-info=;badges={badges};color=;display-name={name};emotes=;id=1;room-id=1;\
+            tmi-sent-ts=1594545155039;user-id={login}-id :{login}!{login}@{login}.tmi.twitch.tv \
+            PRIVMSG #streamer :{text}"
+        );
+        TwitchMessage::try_from(IRCMessage::parse(&raw).unwrap()).unwrap()
+    }
+
+    fn said(actions: &[AppAction]) -> Vec<&str> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                AppAction::Say { message } => Some(message.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_poll() {
+        let mut model = Model::new(
+            &Config::default(),
+            "streamer".to_owned(),
+            Counters::default(),
+            Quotes::default(),
+            ViewerQueue::default(),
+        );
+        let mut chat = |name: &str, badges: &str, text: &str| {
+            let actions = model
+                .handle_twitch_event(privmsg(name, badges, text))
+                .unwrap();
+            said(&actions).into_iter().map(str::to_owned).collect()
+        };
+
+        let start = "!poll start \"Best fruit?\" apple | banana | cherry 30s";
+        let messages: Vec<String> = chat("Viewer", "", start);
+        assert!(messages.is_empty());
+        let messages: Vec<String> = chat("Mod", "moderator/1", start);
+        assert_eq!(
+            messages,
+            ["Poll: Best fruit? 1) apple 2) banana 3) cherry \
+            | Vote with !vote <number> or just the number, 30s left"]
+        );
+
+        let votes = [
+            ("Alice", "2"),
+            ("alice", "!vote 1"), // Already voted
+            ("Bob", "!vote 2"),
+            ("Carol", "3"),
+            ("Dave", "4"), // No such option
+        ];
+        for (name, text) in votes {
+            let messages: Vec<String> = chat(name, "", text);
+            assert!(messages.is_empty());
+        }
+        let poll = model.poll.as_ref().unwrap();
+        assert_eq!(poll.results(), [("apple", 0), ("banana", 2), ("cherry", 1)]);
+
+        assert!(said(&model.update(29.0).unwrap()).is_empty());
+        assert_eq!(
+            said(&model.update(1.0).unwrap()),
+            [
+                "Poll ended: Best fruit? | apple - 0 (0%), banana - 2 (66%), cherry - 1 (33%) \
+            | banana wins"
+            ]
+        );
+        assert!(model.poll.is_none());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            Poll::parse_options("yes | no way 2m"),
+            Some((vec!["yes".to_owned(), "no way".to_owned()], Some(120.0)))
+        );
+        assert_eq!(
+            Poll::parse_options("a|b|"),
+            Some((vec!["a".to_owned(), "b".to_owned()], None))
+        );
+        assert_eq!(Poll::parse_options("just one 10s"), None);
+        assert_eq!(
+            Poll::parse_options("Part 1 | Part 2"),
+            Some((vec!["Part 1".to_owned(), "Part 2".to_owned()], None))
+        );
+        assert_eq!(
+            Poll::parse_options("Top 10 | Top 20"),
+            Some((vec!["Top 10".to_owned(), "Top 20".to_owned()], None))
+        );
+        assert_eq!(
+            Poll::parse_options("yes | no 0s"),
+            Some((vec!["yes".to_owned(), "no 0s".to_owned()], None))
+        );
+    }
+}
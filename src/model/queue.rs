@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/// Viewers waiting for their turn, e.g. to play with the streamer.
+/// Saved, so that people do not lose their spots if the bot restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ViewerQueue {
+    /// Whether viewers can join.
+    #[serde(default)]
+    pub open: bool,
+    #[serde(default)]
+    entries: Vec<QueueEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    /// Twitch id of the viewer, which stays the same when they change their name.
+    pub id: String,
+    pub name: String,
+    /// Whether the viewer skipped ahead of those without priority.
+    #[serde(default)]
+    pub priority: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    Closed,
+    /// The viewer is already in the queue at the position, starting from 1.
+    AlreadyJoined(usize),
+}
+
+impl ViewerQueue {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QueueEntry> {
+        self.entries.iter()
+    }
+
+    /// Position of the viewer in the queue, starting from 1.
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .map(|index| index + 1)
+    }
+
+    /// Adds the viewer to the end of the queue, or after the others with priority.
+    /// Returns the position of the viewer, starting from 1.
+    pub fn join(&mut self, id: &str, name: &str, priority: bool) -> Result<usize, JoinError> {
+        if let Some(position) = self.position(id) {
+            return Err(JoinError::AlreadyJoined(position));
+        }
+        if !self.open {
+            return Err(JoinError::Closed);
+        }
+        let index = if priority {
+            self.entries
+                .iter()
+                .position(|entry| !entry.priority)
+                .unwrap_or(self.entries.len())
+        } else {
+            self.entries.len()
+        };
+        let entry = QueueEntry {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            priority,
+        };
+        self.entries.insert(index, entry);
+        Ok(index + 1)
+    }
+
+    /// Removes the viewer from the queue. Returns `false` if they were not in it.
+    pub fn leave(&mut self, id: &str) -> bool {
+        match self.position(id) {
+            Some(position) => {
+                self.entries.remove(position - 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes and returns up to `count` viewers from the front of the queue.
+    pub fn next(&mut self, count: usize) -> Vec<QueueEntry> {
+        let count = count.min(self.entries.len());
+        self.entries.drain(..count).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue() {
+        let mut queue = ViewerQueue::default();
+        assert_eq!(queue.join("1", "alice", false), Err(JoinError::Closed));
+        queue.open = true;
+        assert_eq!(queue.join("1", "alice", false), Ok(1));
+        assert_eq!(queue.join("2", "bob", false), Ok(2));
+        // Renamed
+        assert_eq!(
+            queue.join("1", "alicia", false),
+            Err(JoinError::AlreadyJoined(1))
+        );
+        assert_eq!(queue.join("3", "sub", true), Ok(1));
+        assert_eq!(queue.join("4", "sub2", true), Ok(2));
+        assert_eq!(queue.position("2"), Some(4));
+
+        assert!(queue.leave("1"));
+        assert!(!queue.leave("1"));
+        let names = |entries: Vec<QueueEntry>| -> Vec<String> {
+            entries.into_iter().map(|entry| entry.name).collect()
+        };
+        assert_eq!(names(queue.next(2)), ["sub", "sub2"]);
+        assert_eq!(names(queue.next(5)), ["bob"]);
+        assert!(queue.is_empty());
+    }
+}
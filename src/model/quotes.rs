@@ -0,0 +1,182 @@
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub id: u64,
+    pub text: String,
+    /// Who said it.
+    pub author: String,
+    /// Date when the quote was added, e.g. `2023-05-20`.
+    pub date: String,
+    /// Game or category of the stream when the quote was added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// Saved quotes, that are referred to by their id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Quotes {
+    /// Category that new quotes are added with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default)]
+    quotes: Vec<Quote>,
+}
+
+/// Which quote to show.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuoteQuery {
+    Random,
+    Id(u64),
+    /// Quotes that contain the text, ignoring case.
+    Search(String),
+}
+
+impl std::fmt::Display for Quote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{} \"{}\" - {}", self.id, self.text, self.author)?;
+        match &self.category {
+            Some(category) => write!(f, " ({}, {})", category, self.date),
+            None => write!(f, " ({})", self.date),
+        }
+    }
+}
+
+impl Quotes {
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Quote> {
+        self.quotes.iter().find(|quote| quote.id == id)
+    }
+
+    pub fn random(&self) -> Option<&Quote> {
+        self.quotes.choose(&mut rand::thread_rng())
+    }
+
+    /// Quotes whose text, author or category contain the text, ignoring case.
+    pub fn search(&self, text: &str) -> Vec<&Quote> {
+        let text = text.to_lowercase();
+        self.quotes
+            .iter()
+            .filter(|quote| {
+                std::iter::once(&quote.text)
+                    .chain([&quote.author])
+                    .chain(&quote.category)
+                    .any(|field| field.to_lowercase().contains(&text))
+            })
+            .collect()
+    }
+
+    /// Adds a quote with the current category and the next free id.
+    pub fn add(&mut self, text: String, author: String, date: String) -> &Quote {
+        let quote = Quote {
+            id: self.next_id(),
+            text,
+            author,
+            date,
+            category: self.category.clone(),
+        };
+        self.quotes.push(quote);
+        self.quotes.last().unwrap()
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<Quote> {
+        let index = self.quotes.iter().position(|quote| quote.id == id)?;
+        Some(self.quotes.remove(index))
+    }
+
+    /// Adds the quotes after the existing ones, giving them new ids.
+    /// Returns the number of imported quotes.
+    pub fn import(&mut self, quotes: Quotes) -> usize {
+        let count = quotes.len();
+        for mut quote in quotes.quotes {
+            quote.id = self.next_id();
+            self.quotes.push(quote);
+        }
+        count
+    }
+
+    fn next_id(&self) -> u64 {
+        self.quotes.iter().map(|quote| quote.id).max().unwrap_or(0) + 1
+    }
+}
+
+/// Current date in UTC, e.g. `2023-05-20`.
+pub fn current_date() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    format_date(secs / 60 / 60 / 24)
+}
+
+/// Formats the number of days since 1970-01-01 as a date.
+fn format_date(days: u64) -> String {
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes() {
+        let mut quotes = Quotes::default();
+        quotes.add(
+            "Hello there".to_owned(),
+            "nertsal".to_owned(),
+            "2023-05-20".to_owned(),
+        );
+        quotes.category = Some("Celeste".to_owned());
+        let quote = quotes.add(
+            "GG".to_owned(),
+            "viewer".to_owned(),
+            "2023-05-21".to_owned(),
+        );
+        assert_eq!(quote.id, 2);
+        assert_eq!(
+            quote.to_string(),
+            "#2 \"GG\" - viewer (Celeste, 2023-05-21)"
+        );
+
+        let ids = |quotes: Vec<&Quote>| quotes.iter().map(|quote| quote.id).collect::<Vec<_>>();
+        assert_eq!(ids(quotes.search("hello")), [1]);
+        assert_eq!(ids(quotes.search("CELESTE")), [2]);
+        assert_eq!(ids(quotes.search("e")), [1, 2]);
+
+        assert_eq!(quotes.remove(1).map(|quote| quote.id), Some(1));
+        assert_eq!(quotes.get(1), None);
+        let imported = quotes.clone();
+        assert_eq!(quotes.import(imported), 1);
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes.get(3).map(|quote| quote.text.as_str()), Some("GG"));
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(11_016), "2000-02-29");
+        assert_eq!(format_date(19_497), "2023-05-20");
+    }
+}
@@ -0,0 +1,180 @@
+use rand::distributions::{Distribution, WeightedIndex};
+
+use super::AuthorityLevel;
+use crate::config::RaffleConfig;
+
+/// A giveaway that chat enters by typing the keyword.
+#[derive(Debug, Clone)]
+pub struct Raffle {
+    /// Message that enters the raffle, compared ignoring case.
+    pub keyword: String,
+    /// Whether new entries are accepted.
+    pub open: bool,
+    /// Users in the order they entered.
+    pub entrants: Vec<Entrant>,
+    /// Users that have already won, in the order they were drawn.
+    pub winners: Vec<Entrant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entrant {
+    /// Twitch id of the user, which stays the same when they change their name.
+    pub id: String,
+    pub name: String,
+    /// Relative chance to win.
+    pub weight: u32,
+}
+
+impl Raffle {
+    pub fn new(keyword: String) -> Self {
+        Self {
+            keyword,
+            open: true,
+            entrants: Vec::new(),
+            winners: Vec::new(),
+        }
+    }
+
+    /// Enters the user if the message is the keyword and entries are open.
+    /// Returns `false` if the user was not entered, e.g. because they already are.
+    pub fn enter(
+        &mut self,
+        user_id: &str,
+        user: &str,
+        authority: AuthorityLevel,
+        message: &str,
+        config: &RaffleConfig,
+    ) -> bool {
+        if !self.open
+            || !message.trim().eq_ignore_ascii_case(&self.keyword)
+            || self.contains(user_id)
+        {
+            return false;
+        }
+        self.entrants.push(Entrant {
+            id: user_id.to_owned(),
+            name: user.to_owned(),
+            weight: config.weight(authority),
+        });
+        true
+    }
+
+    pub fn contains(&self, user_id: &str) -> bool {
+        self.entrants.iter().any(|entrant| entrant.id == user_id)
+    }
+
+    pub fn has_won(&self, user_id: &str) -> bool {
+        self.winners.iter().any(|winner| winner.id == user_id)
+    }
+
+    /// Picks a winner among the entrants that have not won yet, according to their weights.
+    pub fn draw(&mut self, rng: &mut impl rand::Rng) -> Option<&str> {
+        let candidates: Vec<&Entrant> = self
+            .entrants
+            .iter()
+            .filter(|entrant| entrant.weight > 0 && !self.has_won(&entrant.id))
+            .collect();
+        let weights = WeightedIndex::new(candidates.iter().map(|entrant| entrant.weight)).ok()?;
+        let winner = candidates[weights.sample(rng)].clone();
+        self.winners.push(winner);
+        self.winners.last().map(|winner| winner.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_raffle() {
+        let config: RaffleConfig = toml::from_str("weights = { subscriber = 3 }").unwrap();
+        assert_eq!(config.weight(AuthorityLevel::Viewer), 1);
+        assert_eq!(config.weight(AuthorityLevel::Moderator), 3);
+
+        let mut raffle = Raffle::new("!enter".to_owned());
+        let viewer = AuthorityLevel::Viewer;
+        assert!(raffle.enter("1", "Alice", viewer, "!ENTER ", &config));
+        // Renamed
+        assert!(!raffle.enter("1", "Alicia", viewer, "!enter", &config));
+        assert!(!raffle.enter("2", "Bob", viewer, "hi", &config));
+        assert!(raffle.enter("2", "Bob", AuthorityLevel::Subscriber, "!enter", &config));
+        raffle.open = false;
+        assert!(!raffle.enter("3", "Carol", viewer, "!enter", &config));
+        assert_eq!(
+            raffle.entrants,
+            [
+                Entrant {
+                    id: "1".to_owned(),
+                    name: "Alice".to_owned(),
+                    weight: 1
+                },
+                Entrant {
+                    id: "2".to_owned(),
+                    name: "Bob".to_owned(),
+                    weight: 3
+                }
+            ]
+        );
+
+        // Everyone wins once
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let first = raffle.draw(&mut rng).unwrap().to_owned();
+        let second = raffle.draw(&mut rng).unwrap().to_owned();
+        assert_ne!(first, second);
+        assert_eq!(raffle.draw(&mut rng), None);
+        assert!(raffle.has_won("1") && raffle.has_won("2"));
+    }
+
+    #[test]
+    fn test_open_twice() {
+        use crate::{
+            app::AppAction,
+            config::Config,
+            model::{commands::CommandCall, *},
+        };
+
+        fn open(model: &mut Model, keyword: &str) -> Vec<String> {
+            let message = format!("!raffle open {keyword}");
+            let call = CommandCall {
+                message: &message,
+                authority: AuthorityLevel::Broadcaster,
+                user: "streamer",
+                user_id: "1",
+                channel: "streamer",
+            };
+            model
+                .handle_command_call(call)
+                .into_iter()
+                .filter_map(|action| match action {
+                    AppAction::Say { message } => Some(message),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        let mut model = Model::new(
+            &Config::default(),
+            "streamer".to_owned(),
+            Counters::default(),
+            Quotes::default(),
+            ViewerQueue::default(),
+        );
+        assert_eq!(
+            open(&mut model, "!enter"),
+            ["A raffle has started! Type !enter to enter"]
+        );
+        let config = RaffleConfig::default();
+        let raffle = model.raffle.as_mut().unwrap();
+        assert!(raffle.enter("2", "Alice", AuthorityLevel::Viewer, "!enter", &config));
+
+        assert_eq!(
+            open(&mut model, "!join"),
+            ["A raffle is already running, end it with !raffle end"]
+        );
+        let raffle = model.raffle.as_ref().unwrap();
+        assert_eq!(raffle.keyword, "!enter");
+        assert!(raffle.contains("2"));
+    }
+}
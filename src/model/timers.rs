@@ -0,0 +1,152 @@
+use crate::config::TimerConfig;
+
+/// Messages sent to chat periodically.
+#[derive(Debug, Clone, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub config: TimerConfig,
+    pub enabled: bool,
+    /// Time in seconds until the timer can fire.
+    time_left: f64,
+    /// Chat messages since the timer last fired.
+    lines: usize,
+    /// Index of the next message to send.
+    next_message: usize,
+}
+
+impl Timer {
+    fn new(config: TimerConfig) -> Self {
+        Self {
+            enabled: config.enabled,
+            time_left: config.interval,
+            lines: 0,
+            next_message: 0,
+            config,
+        }
+    }
+
+    /// Returns the message to send, if the timer fires.
+    fn update(&mut self, delta_time: f64) -> Option<String> {
+        if !self.enabled || self.config.messages.is_empty() {
+            return None;
+        }
+        self.time_left = (self.time_left - delta_time).max(0.0);
+        // Wait for chat to be active, instead of talking to nobody
+        if self.time_left > 0.0 || self.lines < self.config.min_lines {
+            return None;
+        }
+
+        self.time_left = self.config.interval;
+        self.lines = 0;
+        let index = self.next_message % self.config.messages.len();
+        self.next_message = index + 1;
+        Some(self.config.messages[index].clone())
+    }
+}
+
+impl Timers {
+    pub fn new(config: &[TimerConfig]) -> Self {
+        Self {
+            timers: config.iter().cloned().map(Timer::new).collect(),
+        }
+    }
+
+    /// Applies the new config. Timers with the same name keep their progress,
+    /// but are enabled or disabled as configured.
+    pub fn reload(&mut self, config: &[TimerConfig]) {
+        let mut old = std::mem::take(&mut self.timers);
+        self.timers = config
+            .iter()
+            .map(|config| {
+                let mut timer = Timer::new(config.clone());
+                if let Some(index) = old.iter().position(|old| old.config.name == config.name) {
+                    let old = old.swap_remove(index);
+                    timer.time_left = old.time_left.min(config.interval);
+                    timer.lines = old.lines;
+                    timer.next_message = old.next_message;
+                }
+                timer
+            })
+            .collect();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Timer> {
+        self.timers.iter()
+    }
+
+    /// Counts a message in chat.
+    pub fn chat_line(&mut self) {
+        for timer in &mut self.timers {
+            timer.lines += 1;
+        }
+    }
+
+    /// Returns the messages of the timers that fired.
+    pub fn update(&mut self, delta_time: f64) -> Vec<String> {
+        self.timers
+            .iter_mut()
+            .filter_map(|timer| timer.update(delta_time))
+            .collect()
+    }
+
+    /// Enables or disables the timer with the given name, ignoring case.
+    /// Returns `false` if there is no such timer.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let Some(timer) = self
+            .timers
+            .iter_mut()
+            .find(|timer| timer.config.name.eq_ignore_ascii_case(name))
+        else {
+            return false;
+        };
+        if enabled && !timer.enabled {
+            // Count the interval from the moment it is enabled
+            timer.time_left = timer.config.interval;
+            timer.lines = 0;
+        }
+        timer.enabled = enabled;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer() {
+        let mut timers = Timers::new(&[TimerConfig {
+            name: "socials".to_owned(),
+            interval: 10.0,
+            min_lines: 2,
+            messages: vec!["a".to_owned(), "b".to_owned()],
+            enabled: true,
+        }]);
+
+        assert!(timers.update(10.0).is_empty());
+        // Fires as soon as chat is active enough
+        timers.chat_line();
+        timers.chat_line();
+        assert_eq!(timers.update(0.1), ["a"]);
+
+        timers.chat_line();
+        timers.chat_line();
+        assert!(timers.update(9.0).is_empty());
+        assert_eq!(timers.update(1.0), ["b"]);
+
+        timers.chat_line();
+        timers.chat_line();
+        assert!(timers.set_enabled("SOCIALS", false));
+        assert!(timers.update(10.0).is_empty());
+        assert!(timers.set_enabled("socials", true));
+        timers.chat_line();
+        timers.chat_line();
+        assert!(timers.update(5.0).is_empty());
+        assert_eq!(timers.update(5.0), ["a"]);
+        assert!(!timers.set_enabled("unknown", true));
+    }
+}
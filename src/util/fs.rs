@@ -7,6 +7,16 @@ pub fn read_to_string(path: impl AsRef<std::path::Path>) -> color_eyre::Result<S
     Ok(s)
 }
 
+/// Writes to a temporary file next to the target and then renames it,
+/// so that the target is not left half-written if the bot stops midway.
+fn write_atomic(path: &std::path::Path, content: &[u8]) -> color_eyre::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, content).wrap_err("when writing to file")?;
+    std::fs::rename(&temp, path).wrap_err("when replacing the file")?;
+    Ok(())
+}
+
 /// Reads from a file and attempts to parse its contents from toml format.
 pub fn read_toml<T: serde::de::DeserializeOwned>(
     path: impl AsRef<std::path::Path>,
@@ -22,6 +32,148 @@ pub fn write_toml<T: serde::Serialize>(
     path: impl AsRef<std::path::Path>,
 ) -> color_eyre::Result<()> {
     let content = toml::to_string_pretty(content).wrap_err("when serializing to toml")?;
-    std::fs::write(path, content.as_bytes()).wrap_err("when writing to file")?;
-    Ok(())
+    write_atomic(path.as_ref(), content.as_bytes())
+}
+
+/// Write some content in toml format to the file, editing the existing contents,
+/// so that its comments and the order of its keys are kept.
+pub fn update_toml<T: serde::Serialize>(
+    content: &T,
+    path: impl AsRef<std::path::Path>,
+) -> color_eyre::Result<()> {
+    let path = path.as_ref();
+    let new: toml_edit::Document = toml::to_string(content)
+        .wrap_err("when serializing to toml")?
+        .parse()
+        .wrap_err("when serializing to toml")?;
+    let mut document: toml_edit::Document = match std::fs::read_to_string(path) {
+        Ok(old) => old
+            .parse()
+            .wrap_err_with(|| format!("Failed to parse {path:?}"))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => toml_edit::Document::new(),
+        Err(err) => return Err(err).wrap_err_with(|| format!("Failed to read from {path:?}")),
+    };
+    update_table(document.as_table_mut(), new.as_table());
+    write_atomic(path, document.to_string().as_bytes())
+}
+
+/// Makes `table` hold the values of `new`, keeping the formatting of the existing keys.
+fn update_table(table: &mut toml_edit::Table, new: &toml_edit::Table) {
+    let removed: Vec<String> = table
+        .iter()
+        .map(|(key, _)| key)
+        .filter(|key| !new.contains_key(key))
+        .map(String::from)
+        .collect();
+    for key in removed {
+        table.remove(&key);
+    }
+    for (key, new) in new.iter() {
+        match table.get_mut(key) {
+            Some(item) => update_item(item, new),
+            None => {
+                table.insert(key, new.clone());
+            }
+        }
+    }
+}
+
+fn update_item(item: &mut toml_edit::Item, new: &toml_edit::Item) {
+    use toml_edit::{Item, Value};
+    match (item, new) {
+        (Item::Table(table), Item::Table(new)) => update_table(table, new),
+        (Item::Value(value), Item::Table(new)) => {
+            // Keep the value inline, where it was written
+            let mut table = match value {
+                Value::InlineTable(inline) => inline.clone().into_table(),
+                _ => toml_edit::Table::new(),
+            };
+            update_table(&mut table, new);
+            let mut inline = table.into_inline_table();
+            *inline.decor_mut() = value.decor().clone();
+            *value = Value::InlineTable(inline);
+        }
+        (Item::Value(value), Item::Value(new)) => {
+            let mut new = new.clone();
+            *new.decor_mut() = value.decor().clone();
+            *value = new;
+        }
+        (item, new) => *item = new.clone(),
+    }
+}
+
+/// Reads from a file and attempts to parse its contents from json format.
+pub fn read_json<T: serde::de::DeserializeOwned>(
+    path: impl AsRef<std::path::Path>,
+) -> color_eyre::Result<T> {
+    let content = read_to_string(path)?;
+    let result = serde_json::from_str(&content)?;
+    Ok(result)
+}
+
+/// Write some content in json format to the file.
+pub fn write_json<T: serde::Serialize>(
+    content: &T,
+    path: impl AsRef<std::path::Path>,
+) -> color_eyre::Result<()> {
+    let content = serde_json::to_string_pretty(content).wrap_err("when serializing to json")?;
+    write_atomic(path.as_ref(), content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let path = std::env::temp_dir().join("minbo_test_write_atomic.toml");
+        let content = BTreeMap::from([("count".to_owned(), 1)]);
+        write_toml(&content, &path).unwrap();
+        let read: BTreeMap<String, i32> = read_toml(&path).unwrap();
+        assert_eq!(read, content);
+        assert!(!path.with_extension("toml.tmp").exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_update_table() {
+        let mut document: toml_edit::Document = r#"
+# Default cooldown
+cooldown = 30.0
+
+[commands]
+# Greets
+hi = "Hello"
+bye = { response = "Bye", aliases = ["cya"] } # Parts
+removed = "Gone"
+"#
+        .parse()
+        .unwrap();
+        let new: toml_edit::Document = r#"
+cooldown = 10.0
+
+[commands]
+hi = "Hi"
+bye = { response = "Bye" }
+added = "New"
+"#
+        .parse()
+        .unwrap();
+        update_table(document.as_table_mut(), new.as_table());
+        assert_eq!(
+            document.to_string(),
+            r#"
+# Default cooldown
+cooldown = 10.0
+
+[commands]
+# Greets
+hi = "Hi"
+bye = { response = "Bye" } # Parts
+added = "New"
+"#
+        );
+    }
 }